@@ -29,9 +29,17 @@ pub fn yocto_to_near(yoctos: u128) -> String {
     format!("{}.{}{}", a, "0".repeat(padding), b)
 }
 
-/// Currently not used, but can be useful in case a functio increases (or decreases)
-/// the storage requirements and must pay (or receive) for it.
-pub fn refund_deposit<R>(mut f: impl FnMut() -> R) -> R {
+/// Measures how much the storage usage changed while running `f`, and then
+/// charges for the increase from the attached deposit (or refunds for the
+/// decrease). Any extra attached deposit is refunded to the caller.
+pub fn refund_deposit<R>(f: impl FnMut() -> R) -> R {
+    let (r, _refund) = refund_deposit_amount(f);
+    r
+}
+
+/// The same as [`refund_deposit()`], but also returns how many yoctoNEAR got
+/// refunded to the caller.
+pub fn refund_deposit_amount<R>(mut f: impl FnMut() -> R) -> (R, near_sdk::Balance) {
     use near_sdk::env;
 
     let initial_storage_usage = env::storage_usage();
@@ -42,7 +50,7 @@ pub fn refund_deposit<R>(mut f: impl FnMut() -> R) -> R {
 
     let final_storage_usage = env::storage_usage();
 
-    if final_storage_usage >= initial_storage_usage {
+    let refund = if final_storage_usage >= initial_storage_usage {
         // increased storage usage
         let increased_storage = final_storage_usage - initial_storage_usage;
         let increased_cost = storage_byte_cost * near_sdk::Balance::from(increased_storage);
@@ -52,20 +60,19 @@ pub fn refund_deposit<R>(mut f: impl FnMut() -> R) -> R {
             format!("Must attach {} yoctoNEAR to cover storage", increased_cost)
         );
 
-        let refund = attached_deposit - increased_cost;
-        if refund > 1 {
-            near_sdk::Promise::new(env::predecessor_account_id()).transfer(refund);
-        }
+        attached_deposit - increased_cost
     } else {
         // decreased storage usage
         let decreased_storage = initial_storage_usage - final_storage_usage;
         let decreased_cost = storage_byte_cost * near_sdk::Balance::from(decreased_storage);
 
-        let refund = attached_deposit + decreased_cost;
-        if refund > 1 {
-            near_sdk::Promise::new(env::predecessor_account_id()).transfer(refund);
-        }
+        attached_deposit + decreased_cost
     };
 
-    r
+    if refund > 1 {
+        near_sdk::Promise::new(env::predecessor_account_id()).transfer(refund);
+        (r, refund)
+    } else {
+        (r, 0)
+    }
 }
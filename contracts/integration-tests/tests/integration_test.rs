@@ -3,7 +3,7 @@
 
 use common::sim::{ContractExt, ExecutionExt};
 pub use near_sdk::{
-    json_types::{Base64VecU8, U128, U64},
+    json_types::{Base58CryptoHash, Base64VecU8, U128, U64},
     serde_json::json,
     AccountId,
 };
@@ -876,6 +876,12 @@ fn constant_init_testnet_values_test() {
                         .sum::<u64>()
                         .into(),
                     users_must_claim: set.users_must_claim,
+                    removing: false,
+                    sealed: false,
+                    requests_need_approval: false,
+                    revocable: false,
+                    claim_window: None,
+                    claim_unlock_date: Timestamp::default(),
                 },
             )
         })
@@ -935,6 +941,12 @@ fn constant_init_mainnet_values_test() {
                         .sum::<u64>()
                         .into(),
                     users_must_claim: set.users_must_claim,
+                    removing: false,
+                    sealed: false,
+                    requests_need_approval: false,
+                    revocable: false,
+                    claim_window: None,
+                    claim_unlock_date: Timestamp::default(),
                 },
             )
         })
@@ -1181,3 +1193,3264 @@ fn mint_for_removed_user_test() {
     let res = res.err().unwrap().downcast::<String>().unwrap();
     assert!(res.contains("The account bob is not registered"));
 }
+
+/// Force-mints tokens while limited by the global and per-owner budgets.
+#[test]
+fn force_mint_budget_test() {
+    use seats::types::{MintBudgetConfig, MintBudgetStatus, PendingMintBudget, RewardAmount};
+
+    let (ref root, ref seats, ref alice, ref bob, ref _carol) = init(0, 0, None, None, 0);
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+
+    // Alice also becomes an owner
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "add_owner",
+            json!({ "owner_id": alice.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // each owner can force-mint 6 tokens every 100 seconds,
+    // but all owners together can only force-mint 10 tokens
+    utils::seat_change_mint_budget(seats, root, 100, Some(10), Some(6)).assert_success();
+
+    // the root uses all of it's own budget
+    {
+        utils::seat_try_force_mint(seats, root, bob, 6).assert_success();
+
+        let res = utils::seat_try_force_mint(seats, root, bob, 1);
+        res.assert_failure_with(
+            r"owner mint budget exceeded, 0 raw tokens remaining until \d+ nanoseconds",
+        );
+    }
+
+    // Alice is limited by the global budget
+    {
+        let res = utils::seat_try_force_mint(seats, alice, bob, 5);
+        res.assert_failure_with(
+            r"global mint budget exceeded, 4 raw tokens remaining until \d+ nanoseconds",
+        );
+
+        utils::seat_try_force_mint(seats, alice, bob, 4).assert_success();
+
+        let global: MintBudgetStatus = seats
+            .debug_json_view("get_global_mint_budget", json!({}))
+            .unwrap_json();
+        assert_eq!(global.remaining, Some(RewardAmount(0)));
+        assert!(global.reset_timestamp.is_some());
+
+        let owner: MintBudgetStatus = seats
+            .debug_json_view(
+                "get_owner_mint_budget",
+                json!({ "owner_id": alice.account_id() }),
+            )
+            .unwrap_json();
+        assert_eq!(owner.remaining, Some(RewardAmount(2)));
+    }
+
+    // advances some blocks, so the windows end
+    {
+        let mut runtime = root.borrow_runtime_mut();
+        runtime.produce_blocks(100).unwrap();
+    }
+
+    // the budgets got reset
+    {
+        let global: MintBudgetStatus = seats
+            .debug_json_view("get_global_mint_budget", json!({}))
+            .unwrap_json();
+        assert_eq!(
+            global,
+            MintBudgetStatus {
+                remaining: Some(RewardAmount(10)),
+                reset_timestamp: None
+            }
+        );
+
+        utils::seat_try_force_mint(seats, root, bob, 6).assert_success();
+        assert_eq!(utils::user_info(seats, bob), (16, 0, 0));
+    }
+
+    // lowering below the current usage applies right away
+    {
+        let pending = utils::seat_change_mint_budget(seats, root, 100, Some(10), Some(2));
+        assert_eq!(pending.unwrap_json(), None);
+
+        let res = utils::seat_try_force_mint(seats, root, bob, 1);
+        res.assert_failure_with(
+            r"owner mint budget exceeded, 0 raw tokens remaining until \d+ nanoseconds",
+        );
+    }
+
+    // raising waits for the timelock, and can be canceled by other owners
+    {
+        let pending = utils::seat_change_mint_budget(seats, root, 100, None, None)
+            .unwrap_json()
+            .unwrap();
+        assert_eq!(pending.config.global_limit, None);
+
+        let config: MintBudgetConfig = seats
+            .debug_json_view("get_mint_budget", json!({}))
+            .unwrap_json();
+        assert_eq!(config.owner_limit, Some(RewardAmount(2)));
+        utils::seat_try_force_mint(seats, root, bob, 1)
+            .assert_failure_with("owner mint budget exceeded");
+
+        let canceled: Option<PendingMintBudget> = seats
+            .debug_json_call(
+                alice,
+                "cancel_mint_budget_change",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(canceled, Some(pending));
+
+        let pending: Option<PendingMintBudget> = seats
+            .debug_json_view("get_pending_mint_budget", json!({}))
+            .unwrap_json();
+        assert_eq!(pending, None);
+    }
+}
+
+/// Upgrades the contract's code by the contract itself.
+#[test]
+fn upgrade_test() {
+    use seats::types::Role;
+
+    let (ref root, ref seats, ref alice, ref bob, ref _carol) = init(0, 0, None, None, 0);
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_force_mint(seats, root, alice, 10);
+
+    let code_hash: Option<Base58CryptoHash> = seats
+        .debug_json_view("get_last_upgrade_code_hash", json!({}))
+        .unwrap_json();
+    assert!(code_hash.is_none());
+
+    // the contract must be locked for migration
+    utils::seat_upgrade(seats, root, None)
+        .assert_failure_with("the contract must be locked for migration");
+
+    seats
+        .debug_json_call::<()>(
+            root,
+            "force_start_migration",
+            json!({}),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // Alice doesn't have the Migration role
+    utils::seat_upgrade(seats, alice, None)
+        .assert_failure_with("The account alice doesn't have the Migration role");
+
+    // grants the Migration role to Alice
+    {
+        let granted: bool = seats
+            .debug_json_call(
+                root,
+                "grant_role",
+                json!({ "role": Role::Migration, "account_id": alice.account_id() }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert!(granted);
+
+        let members: Vec<AccountId> = seats
+            .debug_json_view("get_role_members", json!({ "role": Role::Migration }))
+            .unwrap_json();
+        assert_eq!(members, vec![alice.account_id()]);
+    }
+
+    // upgrades with the code sent directly
+    {
+        let res = utils::seat_upgrade(seats, alice, None);
+        res.assert_success();
+        assert!(res
+            .all_logs()
+            .iter()
+            .any(|log| log.starts_with("Upgraded the code to")));
+
+        let code_hash: Option<Base58CryptoHash> = seats
+            .debug_json_view("get_last_upgrade_code_hash", json!({}))
+            .unwrap_json();
+        assert!(code_hash.is_some());
+    }
+
+    // upgrades with the staged code
+    let staged_hash = {
+        let staged_hash: Base58CryptoHash = utils::seat_stage_code(seats, alice).unwrap_json();
+
+        let res = seats.debug_json_call::<bool>(
+            alice,
+            "upgrade_staged",
+            json!({ "code_hash": staged_hash }),
+            utils::GAS,
+            parse_near!("0 N"),
+        );
+        assert!(res
+            .all_logs()
+            .iter()
+            .any(|log| log.starts_with("Upgraded the code to")));
+
+        let code_hash: Option<Base58CryptoHash> = seats
+            .debug_json_view("get_last_upgrade_code_hash", json!({}))
+            .unwrap_json();
+        assert_eq!(code_hash, Some(staged_hash));
+
+        // the staged code got removed
+        let code_hash: Option<Base58CryptoHash> = seats
+            .debug_json_view("get_staged_code_hash", json!({}))
+            .unwrap_json();
+        assert!(code_hash.is_none());
+
+        staged_hash
+    };
+
+    // the contract is still locked
+    utils::transfer(seats, alice, bob, 1)
+        .assert_failure_with("the contract is locked for migration");
+
+    // a failed migration reverts the code and unlocks the contract
+    {
+        let res = utils::seat_upgrade(seats, alice, Some(b"not a wasm"));
+        assert!(res
+            .all_logs()
+            .iter()
+            .any(|log| log == "Failed to upgrade the code, the contract got unlocked"));
+
+        let code_hash: Option<Base58CryptoHash> = seats
+            .debug_json_view("get_last_upgrade_code_hash", json!({}))
+            .unwrap_json();
+        assert_eq!(code_hash, Some(staged_hash));
+    }
+
+    // the previous state is kept
+    utils::transfer(seats, alice, bob, 1).assert_success();
+    assert_eq!(utils::user_info(seats, alice), (9, 0, 0));
+    assert_eq!(utils::user_info(seats, bob), (1, 0, 0));
+}
+
+/// Pauses and unpauses each feature independently.
+#[test]
+fn pause_test() {
+    use seats::types::{Feature, PauseFlags, Role};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_force_mint(seats, root, alice, 10);
+
+    // only owners and guardians can pause
+    utils::seat_pause(seats, carol, &[Feature::Transfers])
+        .assert_failure_with("The account carol doesn't have the Guardian role");
+
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "grant_role",
+            json!({ "role": Role::Guardian, "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // pauses the transfers and the claims
+    {
+        let res = utils::seat_pause(seats, carol, &[Feature::Transfers, Feature::Claims]);
+        res.assert_success();
+        assert_eq!(
+            res.all_logs(),
+            vec![r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"pause","data":[{"account_id":"carol","features":["Transfers","Claims"]}]}"#.to_string()]
+        );
+
+        let paused: PauseFlags = seats.debug_json_view("get_paused", json!({})).unwrap_json();
+        assert_eq!(
+            paused,
+            PauseFlags {
+                transfers: true,
+                minting: false,
+                claims: true,
+                registrations: false
+            }
+        );
+
+        utils::transfer(seats, alice, bob, 1)
+            .assert_failure_with("the Transfers feature is paused");
+        utils::claim(seats, alice).assert_failure_with("the Claims feature is paused");
+        seats
+            .debug_json_call::<Vec<U128>>(
+                root,
+                "forfeit_expired_claims",
+                json!({ "account_ids": [alice.account_id()] }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("the Claims feature is paused");
+
+        // other features are still enabled
+        utils::seat_force_mint(seats, root, bob, 1);
+        utils::seat_register_user(seats, carol);
+    }
+
+    // pauses the minting and the registrations
+    {
+        utils::seat_pause(seats, carol, &[Feature::Minting, Feature::Registrations])
+            .assert_success();
+
+        utils::seat_try_force_mint(seats, root, bob, 1)
+            .assert_failure_with("the Minting feature is paused");
+        utils::start_mint(seats, root).assert_failure_with("the Minting feature is paused");
+        seats
+            .debug_json_call::<()>(
+                root,
+                "storage_deposit",
+                json!({}),
+                utils::GAS,
+                seats::storage_costs::user::BALANCE_REQUIREMENT,
+            )
+            .assert_failure_with("the Registrations feature is paused");
+    }
+
+    // unpauses everything
+    {
+        utils::seat_unpause(
+            seats,
+            carol,
+            &[
+                Feature::Transfers,
+                Feature::Minting,
+                Feature::Claims,
+                Feature::Registrations,
+            ],
+        )
+        .assert_success();
+
+        let paused: PauseFlags = seats.debug_json_view("get_paused", json!({})).unwrap_json();
+        assert_eq!(paused, PauseFlags::default());
+
+        utils::transfer(seats, alice, bob, 1).assert_success();
+        utils::seat_force_mint(seats, root, bob, 1);
+        assert_eq!(utils::user_info(seats, alice), (9, 0, 0));
+        assert_eq!(utils::user_info(seats, bob), (3, 0, 0));
+    }
+}
+
+/// Updates the token metadata, keeping it's history.
+#[test]
+fn metadata_update_test() {
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+    use seats::types::{MetadataVersion, Role};
+
+    let (ref root, ref seats, ref alice, ref bob, ref _carol) = init(0, 0, None, None, 0);
+
+    let metadata = || -> FungibleTokenMetadata {
+        seats
+            .debug_json_view("ft_metadata", json!({}))
+            .unwrap_json()
+    };
+    let original = metadata();
+
+    // Bob doesn't have the Metadata role
+    seats
+        .debug_json_call::<()>(
+            bob,
+            "set_metadata_name",
+            json!({ "name": "SEAT" }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+        .assert_failure_with("The account bob doesn't have the Metadata role");
+
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "grant_role",
+            json!({ "role": Role::Metadata, "account_id": alice.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // updates the icon
+    {
+        let res = seats.debug_json_call::<()>(
+            alice,
+            "set_metadata_icon",
+            json!({ "icon": "data:image/svg+xml,a" }),
+            utils::GAS,
+            parse_near!("1 N"),
+        );
+        res.assert_success();
+        assert!(res.all_logs().contains(
+            &r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"metadata_update","data":[{"account_id":"alice","version":1}]}"#.to_string()
+        ));
+
+        let updated = metadata();
+        assert_eq!(updated.icon, Some("data:image/svg+xml,a".to_string()));
+        assert_eq!(updated.name, original.name);
+    }
+
+    // updates the name and the reference
+    {
+        seats
+            .debug_json_call::<()>(
+                alice,
+                "set_metadata_name",
+                json!({ "name": "SEAT" }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+
+        // the reference hash is required
+        seats
+            .debug_json_call::<()>(
+                alice,
+                "set_metadata_reference",
+                json!({ "reference": "https://example.com/token.json", "reference_hash": null }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_failure_with("the other one must also be `null`");
+
+        seats
+            .debug_json_call::<()>(
+                alice,
+                "set_metadata_reference",
+                json!({
+                    "reference": "https://example.com/token.json",
+                    "reference_hash": Base64VecU8(vec![1; 32])
+                }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+
+        let updated = metadata();
+        assert_eq!(updated.name, "SEAT".to_string());
+        assert_eq!(
+            updated.reference,
+            Some("https://example.com/token.json".to_string())
+        );
+    }
+
+    // the decimals can't be changed
+    {
+        let mut changed = metadata();
+        changed.decimals += 1;
+        seats
+            .debug_json_call::<()>(
+                alice,
+                "set_metadata",
+                json!({ "metadata": changed }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_failure_with("metadata.decimals cannot be changed");
+    }
+
+    // the previous versions are kept
+    {
+        let history: Vec<MetadataVersion> = seats
+            .debug_json_view("get_metadata_history", json!({}))
+            .unwrap_json();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].metadata.icon, original.icon);
+        assert_eq!(history[0].replaced_by, alice.account_id());
+        assert_eq!(history[1].metadata.name, original.name);
+        assert_eq!(history[2].metadata.reference, None);
+    }
+}
+
+/// Charges only the storage actually used by the vesting sets and members,
+/// and refunds the freed storage on their removal.
+#[test]
+fn measured_storage_test() {
+    use seats::types::RemovedVestingSet;
+
+    let (ref root, ref seats, ref alice, ref bob, ref _carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let contract_balance = || seats.account().unwrap().amount;
+
+    utils::seat_register_user(seats, alice);
+
+    // not enough deposit for the storage
+    seats
+        .debug_json_call::<()>(
+            root,
+            "add_vesting_set",
+            json!({
+                "name": set_a,
+                "start_delay_seconds": 0,
+                "expiration_delay_seconds": u32::MAX,
+                "reward": seats::types::RewardAmount(1000),
+                "users_must_claim": false
+            }),
+            utils::GAS,
+            parse_near!("1 yN"),
+        )
+        .assert_failure_with(r"Must attach \d+ yoctoNEAR to cover storage");
+
+    // the extra deposit is refunded
+    let before_set = contract_balance();
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    let set_cost = contract_balance() - before_set;
+    assert!(set_cost < seats::storage_costs::USER_SET);
+
+    let before_user = contract_balance();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    assert!(contract_balance() - before_user < seats::storage_costs::user::VESTING);
+
+    // the freed storage is refunded
+    {
+        let before = contract_balance();
+        seats
+            .debug_json_call::<U128>(
+                root,
+                "remove_vesting_user",
+                json!({ "set": set_a, "account_id": alice.account_id() }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+        assert!(contract_balance() < before);
+
+        let before = contract_balance();
+        let removed: RemovedVestingSet = seats
+            .debug_json_call(
+                root,
+                "remove_vesting_set",
+                json!({ "name": set_a, "force": null }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert!(contract_balance() < before);
+        assert_eq!(removed.removed_members, 0);
+        assert!(removed.refunded.0 > 0);
+        assert!(removed.refunded.0 <= set_cost);
+    }
+
+    // the storage of the members is also refunded on a forced removal
+    {
+        utils::seat_register_user(seats, bob);
+        utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false)
+            .assert_success();
+
+        let before_members = contract_balance();
+        utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+            .assert_success();
+        utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 050_000_000_000)
+            .assert_success();
+        let members_cost = contract_balance() - before_members;
+
+        seats
+            .debug_json_call::<RemovedVestingSet>(
+                root,
+                "remove_vesting_set",
+                json!({ "name": set_a, "force": false }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is not empty");
+
+        let removed: RemovedVestingSet = seats
+            .debug_json_call(
+                root,
+                "remove_vesting_set",
+                json!({ "name": set_a, "force": true }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(removed.removed_members, 2);
+        assert_eq!(removed.info.total_user_percentages.0, 100_000_000_000);
+        // includes the storage of the set and of both members, which was
+        // paid besides the small gas rewards that the contract also received
+        assert!(removed.refunded.0 > members_cost / 2);
+    }
+
+    // unregistering refunds, at most, what the account deposited
+    {
+        let storage_balance = |account: &UserAccount| -> Option<utils::StorageBalance> {
+            seats
+                .debug_json_view(
+                    "storage_balance_of",
+                    json!({ "account_id": account.account_id() }),
+                )
+                .unwrap_json()
+        };
+        let alice_deposit = storage_balance(alice).unwrap().total.0;
+
+        // alice's prepaid history pays for her checkpoints
+        utils::seat_force_mint(seats, root, alice, 100);
+        root.borrow_runtime_mut().produce_blocks(1).unwrap();
+        utils::seat_force_mint(seats, root, alice, 100);
+
+        // and what's left of it is refunded
+        let before = contract_balance();
+        assert!(utils::seat_unregister_user(seats, alice, Some(true)).unwrap_json::<bool>());
+        assert!(before - contract_balance() <= alice_deposit);
+        assert!(before - contract_balance() > seats::storage_costs::user::HISTORY / 2);
+        assert!(storage_balance(alice).is_none());
+    }
+}
+
+/// Removes a vesting set and it's members in pages.
+#[test]
+fn paged_set_removal_test() {
+    use seats::types::{MintState, RemovedVestingSet};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let set_b = &SetName::new("set-b".to_string());
+
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_set(seats, root, set_b.clone(), 0, u32::MAX, 1000, false).assert_success();
+    for user in [alice, bob, carol] {
+        utils::seat_register_user(seats, user);
+        utils::seat_register_vesting_user(seats, root, set_a.clone(), user, 010_000_000_000)
+            .assert_success();
+    }
+    utils::seat_register_vesting_user(seats, root, set_b.clone(), alice, 100_000_000_000)
+        .assert_success();
+
+    // removes the first member right away
+    {
+        let removed: RemovedVestingSet = seats
+            .debug_json_call(
+                root,
+                "remove_vesting_set",
+                json!({ "name": set_a, "force": true, "limit": 1 }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(removed.removed_members, 1);
+        assert_eq!(removed.remaining_members, 2);
+        assert!(removed.refunded.0 > 0);
+
+        let info = utils::seat_get_set(seats, set_a).unwrap_json().unwrap();
+        assert!(info.removing);
+    }
+
+    // the set can't be changed
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), root, 010_000_000_000)
+        .assert_failure_with("set set-a is being removed");
+    seats
+        .debug_json_call::<RemovedVestingSet>(
+            root,
+            "remove_vesting_set",
+            json!({ "name": set_a, "force": true }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_failure_with("set set-a is being removed");
+
+    // minting skips the set
+    {
+        utils::start_mint(seats, root).assert_success();
+        assert_eq!(utils::step_mint(seats, root, 100), MintState::Standby);
+
+        // only the reward from set b was minted
+        assert_eq!(utils::get_user(seats, alice).balance.0, 1000);
+        assert_eq!(utils::get_user(seats, bob).balance.0, 0);
+        assert_eq!(utils::get_user(seats, carol).balance.0, 0);
+
+        let info = utils::seat_get_set(seats, set_a).unwrap_json().unwrap();
+        assert_eq!(info.generation, 0);
+        assert_eq!(info.total_rewarded.0, 0);
+    }
+
+    // removes the remaining members
+    {
+        let removed: RemovedVestingSet = seats
+            .debug_json_call(
+                root,
+                "step_remove_vesting_set",
+                json!({ "name": set_a, "limit": 1 }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(removed.removed_members, 1);
+        assert_eq!(removed.remaining_members, 1);
+
+        let removed: RemovedVestingSet = seats
+            .debug_json_call(
+                root,
+                "step_remove_vesting_set",
+                json!({ "name": set_a, "limit": null }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(removed.removed_members, 1);
+        assert_eq!(removed.remaining_members, 0);
+        assert_eq!(removed.info.total_user_percentages.0, 030_000_000_000);
+
+        assert!(utils::seat_get_set(seats, set_a).unwrap_json().is_none());
+        seats
+            .debug_json_call::<RemovedVestingSet>(
+                root,
+                "step_remove_vesting_set",
+                json!({ "name": set_a, "limit": null }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("missing set set-a");
+    }
+
+    // set b is kept
+    assert!(utils::seat_get_set(seats, set_b).unwrap_json().is_some());
+}
+
+/// Initializes the contract in it's setup phase, imports the vesting sets and
+/// their members in batches, and then seals the setup.
+#[test]
+fn staged_init_test() {
+    use seats::init::SetMembers;
+    use seats::types::{MigrationState, RewardAmount, RewardPercentage};
+
+    let (ref root, _, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+    let seats = &utils::setup_seats_staged(root, "seats2", 0, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let set_b = &SetName::new("set-b".to_string());
+    let set_members = |set: &SetName, members: Vec<(&UserAccount, u64)>| SetMembers {
+        set: set.clone(),
+        reward: RewardAmount(1000),
+        start_delay_seconds: 0,
+        expiration_delay_seconds: u32::MAX,
+        users_must_claim: false,
+        members: members
+            .into_iter()
+            .map(|(user, percentage)| (user.account_id(), RewardPercentage(percentage)))
+            .collect(),
+    };
+
+    // the contract is locked during the setup
+    {
+        let state: MigrationState = seats
+            .debug_json_view("get_migration_state", json!({}))
+            .unwrap_json();
+        assert_eq!(state, MigrationState::Setup);
+
+        utils::seat_try_force_mint(seats, root, root, 1)
+            .assert_failure_with("the contract is locked for migration");
+        utils::start_mint(seats, root).assert_failure_with("the contract is locked for migration");
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_end_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("the setup must be sealed by seal_setup");
+    }
+
+    // only owners can import
+    utils::seat_import_set_members(seats, alice, vec![set_members(set_a, vec![(alice, 1)])])
+        .assert_failure_with("The account alice is not a contract owner");
+
+    // imports the members in two batches, registering their accounts
+    {
+        utils::seat_import_set_members(
+            seats,
+            root,
+            vec![set_members(set_a, vec![(alice, 030_000_000_000)])],
+        )
+        .assert_success();
+        utils::seat_import_set_members(
+            seats,
+            root,
+            vec![
+                set_members(set_a, vec![(bob, 070_000_000_000)]),
+                set_members(set_b, vec![(carol, 050_000_000_000)]),
+            ],
+        )
+        .assert_success();
+
+        // a batch that goes above 100% is rejected as a whole
+        utils::seat_import_set_members(
+            seats,
+            root,
+            vec![
+                set_members(set_b, vec![(alice, 010_000_000_000)]),
+                set_members(set_a, vec![(carol, 000_000_000_001)]),
+            ],
+        )
+        .assert_failure_with("percentage 100000000001 is too high");
+
+        assert_eq!(utils::user_info(seats, alice), (0, 0, 300));
+        assert_eq!(utils::user_info(seats, bob), (0, 0, 700));
+        assert_eq!(utils::user_info(seats, carol), (0, 0, 500));
+        assert_eq!(
+            utils::seat_get_set(seats, set_a)
+                .unwrap_json()
+                .unwrap()
+                .total_user_percentages,
+            RewardPercentage(100_000_000_000)
+        );
+    }
+
+    // seals the setup, unlocking the contract
+    {
+        let seal = |caller: &UserAccount| {
+            seats.debug_json_call::<()>(
+                caller,
+                "seal_setup",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+        };
+        seal(alice).assert_failure_with("The account alice is not a contract owner");
+        seal(root).assert_success();
+        seal(root).assert_failure_with("setup phase not in progress");
+
+        utils::seat_import_set_members(seats, root, vec![set_members(set_b, vec![(bob, 1)])])
+            .assert_failure_with("setup phase not in progress");
+
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != seats::types::MintState::Standby {}
+
+        assert_eq!(utils::user_info(seats, alice), (300, 0, 300));
+        utils::transfer(seats, alice, bob, 100).assert_success();
+        assert_eq!(utils::user_info(seats, bob), (800, 0, 700));
+    }
+}
+
+/// Adds, changes and removes many vesting members at once.
+#[test]
+fn bulk_vesting_users_test() {
+    use seats::types::RewardPercentage;
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let members = |members: &[(&UserAccount, u64)]| {
+        members
+            .iter()
+            .map(|(user, percentage)| (user.account_id(), RewardPercentage(*percentage)))
+            .collect::<Vec<_>>()
+    };
+    let add = |members: Vec<(AccountId, RewardPercentage)>, register: Option<bool>| {
+        seats.debug_json_call::<()>(
+            root,
+            "add_vesting_users",
+            json!({ "set": set_a, "members": members, "register": register }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+
+    // bob and carol are not registered
+    add(
+        members(&[(alice, 020_000_000_000), (bob, 030_000_000_000)]),
+        None,
+    )
+    .assert_failure_with("account bob is not registered");
+
+    // the whole batch is rejected if it goes above 100%
+    add(
+        members(&[
+            (alice, 020_000_000_000),
+            (bob, 030_000_000_000),
+            (carol, 050_000_000_001),
+        ]),
+        Some(true),
+    )
+    .assert_failure_with("percentage 100000000001 is too high");
+    assert_eq!(utils::user_info(seats, alice), (0, 0, 0));
+    let bob_storage: Option<utils::StorageBalance> = seats
+        .debug_json_view(
+            "storage_balance_of",
+            json!({ "account_id": bob.account_id() }),
+        )
+        .unwrap_json();
+    assert!(bob_storage.is_none());
+
+    // registers bob and carol
+    {
+        let before = seats.account().unwrap().amount;
+        add(
+            members(&[
+                (alice, 020_000_000_000),
+                (bob, 030_000_000_000),
+                (carol, 050_000_000_000),
+            ]),
+            Some(true),
+        )
+        .assert_success();
+        // the extra deposit is refunded
+        assert!(seats.account().unwrap().amount - before < parse_near!("1 N"));
+
+        assert_eq!(utils::user_info(seats, alice), (0, 0, 200));
+        assert_eq!(utils::user_info(seats, bob), (0, 0, 300));
+        assert_eq!(utils::user_info(seats, carol), (0, 0, 500));
+    }
+
+    // moves percentages between the members
+    {
+        let change = |members: Vec<(AccountId, RewardPercentage)>| {
+            seats.debug_json_call::<Vec<RewardPercentage>>(
+                root,
+                "change_vesting_users",
+                json!({ "set": set_a, "members": members }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+        };
+
+        // would only go above 100% in the middle of the batch
+        let previous = change(members(&[
+            (alice, 070_000_000_000),
+            (carol, 000_000_000_000),
+        ]))
+        .unwrap_json();
+        assert_eq!(
+            previous,
+            vec![
+                RewardPercentage(020_000_000_000),
+                RewardPercentage(050_000_000_000)
+            ]
+        );
+        assert_eq!(utils::user_info(seats, alice), (0, 0, 700));
+        assert_eq!(utils::user_info(seats, carol), (0, 0, 0));
+
+        change(members(&[(bob, 040_000_000_000)]))
+            .assert_failure_with("percentage 110000000000 is too high");
+        change(members(&[
+            (alice, 010_000_000_000),
+            (root, 010_000_000_000),
+        ]))
+        .assert_failure_with("account root not registered on set set-a");
+        assert_eq!(utils::user_info(seats, alice), (0, 0, 700));
+    }
+
+    // removes many members
+    {
+        let remove = |account_ids: Vec<AccountId>| {
+            seats.debug_json_call::<Vec<RewardPercentage>>(
+                root,
+                "remove_vesting_users",
+                json!({ "set": set_a, "account_ids": account_ids }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+        };
+
+        remove(vec![alice.account_id(), root.account_id()])
+            .assert_failure_with("account root not registered on set set-a");
+
+        let before = seats.account().unwrap().amount;
+        let previous = remove(vec![alice.account_id(), carol.account_id()]).unwrap_json();
+        assert_eq!(
+            previous,
+            vec![
+                RewardPercentage(070_000_000_000),
+                RewardPercentage(000_000_000_000)
+            ]
+        );
+        // the freed storage is refunded
+        assert!(seats.account().unwrap().amount < before);
+
+        assert_eq!(
+            utils::seat_get_set(seats, set_a)
+                .unwrap_json()
+                .unwrap()
+                .total_user_percentages,
+            RewardPercentage(030_000_000_000)
+        );
+    }
+}
+
+/// Moves a vesting member from a set into another.
+#[test]
+fn move_vesting_user_test() {
+    use seats::types::RewardPercentage;
+
+    let (ref root, ref seats, ref alice, ref bob, ref _carol) = init(0, 0, None, None, 0);
+
+    let advisors = &SetName::new("advisors".to_string());
+    let team = &SetName::new("team".to_string());
+    let move_user = |from_set: &SetName, to_set: &SetName, user: &UserAccount, percentage: u64| {
+        seats.debug_json_call::<RewardPercentage>(
+            root,
+            "move_vesting_user",
+            json!({
+                "from_set": from_set,
+                "to_set": to_set,
+                "account_id": user.account_id(),
+                "new_percentage": RewardPercentage(percentage)
+            }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, advisors.clone(), 0, u32::MAX, 1000, false)
+        .assert_success();
+    utils::seat_register_set(seats, root, team.clone(), 0, u32::MAX, 2000, false).assert_success();
+    utils::seat_register_vesting_user(seats, root, advisors.clone(), alice, 040_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, team.clone(), bob, 080_000_000_000)
+        .assert_success();
+    assert_eq!(utils::user_info(seats, alice), (0, 0, 400));
+
+    move_user(advisors, advisors, alice, 010_000_000_000)
+        .assert_failure_with("the sets must be different");
+    move_user(team, advisors, alice, 010_000_000_000)
+        .assert_failure_with("account alice not registered on set team");
+    // the new set would go above 100%
+    move_user(advisors, team, alice, 030_000_000_000)
+        .assert_failure_with("percentage 110000000000 is too high");
+
+    let res = move_user(advisors, team, alice, 020_000_000_000);
+    assert_eq!(res.unwrap_json(), RewardPercentage(040_000_000_000));
+    assert_eq!(
+        res.all_logs(),
+        vec![r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"vesting_user_move","data":[{"account_id":"alice","from_set":"advisors","to_set":"team","previous_percentage":"40000000000","new_percentage":"20000000000"}]}"#.to_string()]
+    );
+
+    // alice is only on the team set
+    assert_eq!(utils::user_info(seats, alice), (0, 0, 400));
+    let set_percentages = |set: &SetName| {
+        utils::seat_get_set(seats, set)
+            .unwrap_json()
+            .unwrap()
+            .total_user_percentages
+    };
+    assert_eq!(set_percentages(advisors), RewardPercentage(0));
+    assert_eq!(set_percentages(team), RewardPercentage(100_000_000_000));
+
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != seats::types::MintState::Standby {}
+    assert_eq!(utils::user_info(seats, alice), (400, 0, 400));
+}
+
+/// Seals a vesting set, after which it can no longer be changed.
+#[test]
+fn sealed_vesting_set_test() {
+    use seats::types::{RewardAmount, RewardPercentage, Timestamp, VestingUserSetInfo};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let set_b = &SetName::new("set-b".to_string());
+    let seal = |set: &SetName, require_full: Option<bool>| {
+        seats.debug_json_call::<VestingUserSetInfo>(
+            root,
+            "seal_vesting_set",
+            json!({ "name": set, "require_full": require_full }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_set(seats, root, set_b.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 060_000_000_000)
+        .assert_success();
+
+    // a revocable set, with a treasury to revoke to
+    utils::seat_register_user(seats, carol);
+    seats
+        .debug_json_call::<()>(
+            root,
+            "set_treasury",
+            json!({ "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "change_vesting_set_revocable",
+            json!({ "name": set_a, "revocable": true }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // only owners can seal
+    seats
+        .debug_json_call::<VestingUserSetInfo>(
+            alice,
+            "seal_vesting_set",
+            json!({ "name": set_a, "require_full": null }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_failure_with("The account alice is not a contract owner");
+
+    seal(set_a, Some(true))
+        .assert_failure_with("set set-a percentages add up to 60000000000, not to 100%");
+
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 040_000_000_000)
+        .assert_success();
+    let info = seal(set_a, Some(true)).unwrap_json();
+    assert!(info.sealed);
+    assert!(
+        utils::seat_get_set(seats, set_a)
+            .unwrap_json()
+            .unwrap()
+            .sealed
+    );
+
+    seal(set_a, None).assert_failure_with("set set-a is sealed");
+
+    // every change is rejected
+    {
+        seats
+            .debug_json_call::<VestingUserSetInfo>(
+                root,
+                "change_vesting_set",
+                json!({
+                    "name": set_a,
+                    "new_start_date": Timestamp(0),
+                    "new_expiration_date": Timestamp(u64::MAX),
+                    "new_reward": RewardAmount(1),
+                    "new_users_must_claim": false
+                }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+        seats
+            .debug_json_call::<RewardPercentage>(
+                root,
+                "change_vesting_user",
+                json!({
+                    "set": set_a,
+                    "account_id": alice.account_id(),
+                    "new_percentage": RewardPercentage(0)
+                }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+        seats
+            .debug_json_call::<RewardPercentage>(
+                root,
+                "remove_vesting_user",
+                json!({ "set": set_a, "account_id": alice.account_id() }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+        seats
+            .debug_json_call::<RewardPercentage>(
+                root,
+                "move_vesting_user",
+                json!({
+                    "from_set": set_a,
+                    "to_set": set_b,
+                    "account_id": alice.account_id(),
+                    "new_percentage": RewardPercentage(0)
+                }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+        seats
+            .debug_json_call::<()>(
+                root,
+                "remove_vesting_set",
+                json!({ "name": set_a, "force": true }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+        seats
+            .debug_json_call::<seats::types::Revocation>(
+                root,
+                "revoke_vesting_user",
+                json!({ "set": set_a, "account_id": alice.account_id(), "claw_back": null }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_failure_with("set set-a is sealed");
+    }
+
+    // the sealed set still mints
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != seats::types::MintState::Standby {}
+    assert_eq!(utils::user_info(seats, alice), (600, 0, 600));
+    assert_eq!(utils::user_info(seats, bob), (400, 0, 400));
+}
+
+/// Members name reward receivers and transfer their positions, with and
+/// without owner approval.
+#[test]
+fn vesting_requests_test() {
+    use seats::types::{MintState, RewardPercentage, VestingRequest};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let set_receiver = |member: &UserAccount, receiver: Option<&UserAccount>| {
+        seats.debug_json_call::<bool>(
+            member,
+            "set_vesting_receiver",
+            json!({
+                "set": set_a,
+                "receiver_id": receiver.map(|receiver| receiver.account_id())
+            }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+    let transfer_position = |member: &UserAccount, new_member: &UserAccount| {
+        seats.debug_json_call::<bool>(
+            member,
+            "transfer_vesting_position",
+            json!({ "set": set_a, "new_account_id": new_member.account_id() }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+    let approve = |member: &UserAccount| {
+        seats.debug_json_call::<VestingRequest>(
+            root,
+            "approve_vesting_request",
+            json!({ "set": set_a, "account_id": member.account_id() }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+
+    // only members can make requests, to registered accounts
+    set_receiver(bob, Some(alice)).assert_failure_with("account bob not registered on set set-a");
+    set_receiver(alice, Some(carol)).assert_failure_with("account carol is not registered");
+
+    // without approval, the receiver is set right away
+    assert!(set_receiver(alice, Some(bob)).unwrap_json());
+    let receiver: Option<AccountId> = seats
+        .debug_json_view(
+            "get_vesting_receiver",
+            json!({ "set": set_a, "account_id": alice.account_id() }),
+        )
+        .unwrap_json();
+    assert_eq!(receiver, Some(bob.account_id()));
+
+    mint();
+    assert_eq!(utils::user_info(seats, alice), (0, 0, 500));
+    assert_eq!(utils::user_info(seats, bob).0, 500);
+
+    // with approval, the requests stay pending
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "change_vesting_set_approval",
+            json!({ "name": set_a, "requests_need_approval": true }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    utils::seat_register_user(seats, carol);
+    assert!(!transfer_position(alice, carol).unwrap_json());
+    let request: Option<VestingRequest> = seats
+        .debug_json_view(
+            "get_vesting_request",
+            json!({ "set": set_a, "account_id": alice.account_id() }),
+        )
+        .unwrap_json();
+    assert_eq!(
+        request,
+        Some(VestingRequest::Transfer {
+            new_account_id: carol.account_id()
+        })
+    );
+
+    // alice still receives, through bob
+    mint();
+    assert_eq!(utils::user_info(seats, bob).0, 1000);
+    assert_eq!(utils::user_info(seats, carol), (0, 0, 0));
+
+    // only owners approve
+    seats
+        .debug_json_call::<VestingRequest>(
+            alice,
+            "approve_vesting_request",
+            json!({ "set": set_a, "account_id": alice.account_id() }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+        .assert_failure_with("The account alice is not a contract owner");
+    approve(alice).assert_success();
+    approve(alice).assert_failure_with("account alice has no request on set set-a");
+
+    // carol got the position, without alice's receiver
+    assert_eq!(utils::user_info(seats, alice), (0, 0, 0));
+    assert_eq!(utils::user_info(seats, carol), (0, 0, 500));
+    let receiver: Option<AccountId> = seats
+        .debug_json_view(
+            "get_vesting_receiver",
+            json!({ "set": set_a, "account_id": carol.account_id() }),
+        )
+        .unwrap_json();
+    assert_eq!(receiver, None);
+
+    mint();
+    assert_eq!(utils::user_info(seats, carol).0, 500);
+    assert_eq!(
+        seats
+            .debug_json_view::<RewardPercentage>(
+                "get_vesting_user",
+                json!({ "set": set_a, "account_id": carol.account_id() }),
+            )
+            .unwrap_json(),
+        RewardPercentage(050_000_000_000)
+    );
+
+    // members can cancel their requests
+    assert!(!set_receiver(carol, Some(alice)).unwrap_json());
+    seats
+        .debug_json_call::<VestingRequest>(
+            carol,
+            "cancel_vesting_request",
+            json!({ "set": set_a, "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+    approve(carol).assert_failure_with("account carol has no request on set set-a");
+
+    // sealed sets reject the transfers and their approvals
+    assert!(!transfer_position(carol, alice).unwrap_json());
+    seats
+        .debug_json_call::<seats::types::VestingUserSetInfo>(
+            root,
+            "seal_vesting_set",
+            json!({ "name": set_a, "require_full": null }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+    transfer_position(carol, alice).assert_failure_with("set set-a is sealed");
+    approve(carol).assert_failure_with("set set-a is sealed");
+
+    // but the pending requests can still be canceled, refunding their storage
+    let before = seats.account().unwrap().amount;
+    seats
+        .debug_json_call::<VestingRequest>(
+            carol,
+            "cancel_vesting_request",
+            json!({ "set": set_a, "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("1 yN"),
+        )
+        .assert_success();
+    assert!(seats.account().unwrap().amount < before);
+
+    // and the members can still name receivers
+    assert!(!set_receiver(carol, Some(alice)).unwrap_json());
+    approve(carol).assert_success();
+    let receiver: Option<AccountId> = seats
+        .debug_json_view(
+            "get_vesting_receiver",
+            json!({ "set": set_a, "account_id": carol.account_id() }),
+        )
+        .unwrap_json();
+    assert_eq!(receiver, Some(alice.account_id()));
+}
+
+/// Revokes members from a revocable set, giving their shares to the
+/// treasury.
+#[test]
+fn revocation_test() {
+    use seats::types::{MintState, Revocation, RewardPercentage};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let revoke = |user: &UserAccount, claw_back: bool| {
+        seats.debug_json_call::<Revocation>(
+            root,
+            "revoke_vesting_user",
+            json!({ "set": set_a, "account_id": user.account_id(), "claw_back": claw_back }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+    };
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 030_000_000_000)
+        .assert_success();
+    utils::seat_force_mint(seats, root, alice, 7);
+
+    mint();
+    assert_eq!(utils::user_info(seats, alice), (7, 500, 500));
+
+    revoke(alice, false).assert_failure_with("treasury not configured");
+    seats
+        .debug_json_call::<()>(
+            root,
+            "set_treasury",
+            json!({ "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    revoke(alice, false).assert_failure_with("set set-a is not revocable");
+    seats
+        .debug_json_call::<bool>(
+            root,
+            "change_vesting_set_revocable",
+            json!({ "name": set_a, "revocable": true }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+    revoke(carol, false).assert_failure_with("the treasury cannot be revoked");
+
+    // the unclaimed tokens are clawed back
+    {
+        let res = revoke(alice, true);
+        assert_eq!(
+            res.unwrap_json(),
+            Revocation {
+                percentage: RewardPercentage(050_000_000_000),
+                clawed_back: U128(500)
+            }
+        );
+        assert_eq!(
+            res.all_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"carol","amount":"500","memo":"revoke"}]}"#.to_string(),
+                r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"vesting_user_revoke","data":[{"account_id":"alice","set":"set-a","treasury_id":"carol","percentage":"50000000000","clawed_back":"500"}]}"#.to_string(),
+            ]
+        );
+
+        assert_eq!(utils::user_info(seats, alice), (7, 0, 0));
+        assert_eq!(utils::user_info(seats, carol), (500, 0, 500));
+        assert_eq!(utils::total_supply(seats), 7 + 500 + 300);
+    }
+
+    // the treasury's share grows, without clawing back
+    {
+        let revocation = revoke(bob, false).unwrap_json();
+        assert_eq!(revocation.clawed_back, U128(0));
+        assert_eq!(utils::user_info(seats, bob), (0, 300, 0));
+        assert_eq!(
+            seats
+                .debug_json_view::<RewardPercentage>(
+                    "get_vesting_user",
+                    json!({ "set": set_a, "account_id": carol.account_id() }),
+                )
+                .unwrap_json(),
+            RewardPercentage(080_000_000_000)
+        );
+    }
+
+    mint();
+    assert_eq!(utils::user_info(seats, alice), (7, 0, 0));
+    assert_eq!(utils::user_info(seats, carol), (500, 800, 800));
+
+    // the tokens given to the member's receiver are also clawed back
+    {
+        let set_b = &SetName::new("set-b".to_string());
+        let set_receiver = |receiver: Option<&UserAccount>| {
+            seats.debug_json_call::<bool>(
+                bob,
+                "set_vesting_receiver",
+                json!({
+                    "set": set_b,
+                    "receiver_id": receiver.map(|receiver| receiver.account_id())
+                }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+        };
+
+        utils::seat_register_set(seats, root, set_b.clone(), 0, u32::MAX, 1000, true)
+            .assert_success();
+        seats
+            .debug_json_call::<bool>(
+                root,
+                "change_vesting_set_revocable",
+                json!({ "name": set_b, "revocable": true }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+        utils::seat_register_vesting_user(seats, root, set_b.clone(), bob, 100_000_000_000)
+            .assert_success();
+        assert!(set_receiver(Some(alice)).unwrap_json());
+
+        mint();
+        assert_eq!(utils::user_info(seats, alice), (7, 1000, 0));
+
+        // the receiver can't be dropped before it claims
+        set_receiver(None).assert_failure_with(
+            "receiver alice still has unclaimed tokens from account bob on set set-b",
+        );
+
+        let res = seats.debug_json_call::<Revocation>(
+            root,
+            "revoke_vesting_user",
+            json!({ "set": set_b, "account_id": bob.account_id(), "claw_back": true }),
+            utils::GAS,
+            parse_near!("1 N"),
+        );
+        assert_eq!(res.unwrap_json::<Revocation>().clawed_back, U128(1000));
+        assert_eq!(utils::user_info(seats, alice), (7, 0, 0));
+        assert_eq!(utils::user_info(seats, carol), (1500, 1600, 1800));
+    }
+}
+
+/// Claims partially, on behalf of others, and then transfers the claimed
+/// tokens.
+#[test]
+fn claims_test() {
+    use seats::types::{Feature, MintState, Role};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let claim = |user: &UserAccount, amount: Option<u128>| match amount {
+        Some(amount) => seats.debug_json_call::<U128>(
+            user,
+            "claim_amount",
+            json!({ "amount": U128(amount) }),
+            utils::GAS,
+            parse_near!("0 N"),
+        ),
+        None => utils::claim(seats, user),
+    };
+    let claim_and_transfer_call = |user: &UserAccount, receiver: &UserAccount, deposit: u128| {
+        seats.debug_json_call::<U128>(
+            user,
+            "claim_and_transfer_call",
+            json!({ "receiver_id": receiver.account_id(), "msg": "" }),
+            utils::GAS,
+            deposit,
+        )
+    };
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 050_000_000_000)
+        .assert_success();
+
+    mint();
+    assert_eq!(utils::user_info(seats, alice), (0, 500, 500));
+
+    // claims partially
+    {
+        assert_eq!(claim(alice, Some(200)).unwrap_json(), U128(200));
+        assert_eq!(utils::user_info(seats, alice), (200, 300, 500));
+        claim(alice, Some(400))
+            .assert_failure_with("cannot claim 400 raw tokens, only 300 are claimable");
+    }
+
+    // can't claim while locked for migration
+    {
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_start_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+        claim(alice, None).assert_failure_with("the contract is locked for migration");
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_end_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+    }
+
+    // keepers claim on behalf of others
+    {
+        let claim_for = |caller: &UserAccount| {
+            seats.debug_json_call::<Vec<U128>>(
+                caller,
+                "claim_for",
+                json!({ "account_ids": [alice.account_id(), bob.account_id(), "unregistered"] }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+        };
+
+        claim_for(carol).assert_failure_with("The account carol doesn't have the Keeper role");
+        seats
+            .debug_json_call::<bool>(
+                root,
+                "grant_role",
+                json!({ "role": Role::Keeper, "account_id": carol.account_id() }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+
+        assert_eq!(
+            claim_for(carol).unwrap_json(),
+            vec![U128(300), U128(500), U128(0)]
+        );
+        assert_eq!(utils::user_info(seats, alice), (500, 0, 500));
+        assert_eq!(utils::user_info(seats, bob), (500, 0, 500));
+    }
+
+    // claims and transfers
+    {
+        mint();
+        assert_eq!(utils::user_info(seats, alice), (500, 500, 500));
+
+        claim_and_transfer_call(alice, bob, parse_near!("0 N"))
+            .assert_failure_with("Requires attached deposit of exactly 1 yoctoNEAR");
+
+        utils::seat_pause(seats, root, &[Feature::Transfers]).assert_success();
+        claim_and_transfer_call(alice, bob, parse_near!("1 yN"))
+            .assert_failure_with("the Transfers feature is paused");
+        utils::seat_unpause(seats, root, &[Feature::Transfers]).assert_success();
+
+        // bob is not a contract, so the tokens get refunded
+        claim_and_transfer_call(alice, bob, parse_near!("1 yN"));
+        assert_eq!(utils::user_info(seats, alice), (1000, 0, 500));
+        assert_eq!(utils::user_info(seats, bob), (500, 500, 500));
+
+        claim_and_transfer_call(alice, bob, parse_near!("1 yN"))
+            .assert_failure_with("nothing to claim");
+    }
+}
+
+/// Forfeits the rewards that were not claimed within the set's claim window.
+#[test]
+fn claim_window_test() {
+    use seats::types::{ClaimWindow, MintState};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let change_claim_window = |claim_window: Option<ClaimWindow>| {
+        seats.debug_json_call::<Option<ClaimWindow>>(
+            root,
+            "change_vesting_set_claim_window",
+            json!({ "name": set_a, "claim_window": claim_window }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+    };
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+    let wait = |blocks: u64| {
+        let mut runtime = root.borrow_runtime_mut();
+        runtime.produce_blocks(blocks).unwrap();
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 050_000_000_000)
+        .assert_success();
+    seats
+        .debug_json_call::<()>(
+            root,
+            "set_treasury",
+            json!({ "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    change_claim_window(Some(ClaimWindow {
+        seconds: 100,
+        burn: false,
+    }))
+    .assert_success();
+
+    mint();
+    utils::claim(seats, alice).assert_success();
+    assert_eq!(utils::user_info(seats, alice), (500, 0, 500));
+    assert_eq!(utils::user_info(seats, bob), (0, 500, 500));
+
+    // the tokens are forfeited to the treasury
+    {
+        mint();
+        wait(200);
+
+        let res = seats.debug_json_call::<Vec<U128>>(
+            root,
+            "forfeit_expired_claims",
+            json!({ "account_ids": [alice.account_id(), bob.account_id()] }),
+            utils::GAS,
+            parse_near!("0 N"),
+        );
+        assert_eq!(res.unwrap_json(), vec![U128(500), U128(1000)]);
+        assert!(res.all_logs().contains(
+            &r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"carol","amount":"500","memo":"forfeit"}]}"#.to_string()
+        ));
+
+        assert_eq!(utils::user_info(seats, alice), (500, 0, 500));
+        assert_eq!(utils::user_info(seats, bob), (0, 0, 500));
+        assert_eq!(utils::user_info(seats, carol), (1500, 0, 0));
+        assert_eq!(utils::total_supply(seats), 2000);
+    }
+
+    // the tokens are burned, while claiming
+    {
+        change_claim_window(Some(ClaimWindow {
+            seconds: 100,
+            burn: true,
+        }))
+        .assert_success();
+
+        mint();
+        wait(200);
+
+        assert_eq!(utils::claim(seats, bob).unwrap_json(), U128(0));
+        assert_eq!(utils::user_info(seats, bob), (0, 0, 500));
+        assert_eq!(utils::user_info(seats, alice), (500, 500, 500));
+        assert_eq!(utils::total_supply(seats), 2500);
+    }
+
+    // without a window, the tokens stay claimable
+    {
+        change_claim_window(None).assert_success();
+        wait(200);
+        assert_eq!(utils::claim(seats, alice).unwrap_json(), U128(500));
+        assert_eq!(utils::total_supply(seats), 2500);
+    }
+
+    let forfeit = || -> Vec<U128> {
+        seats
+            .debug_json_call::<Vec<U128>>(
+                root,
+                "forfeit_expired_claims",
+                json!({ "account_ids": [alice.account_id(), bob.account_id()] }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json()
+    };
+
+    // only the rewards of the older generations are forfeited
+    {
+        change_claim_window(Some(ClaimWindow {
+            seconds: 150,
+            burn: false,
+        }))
+        .assert_success();
+
+        mint();
+        wait(100);
+        mint();
+        assert_eq!(utils::user_info(seats, alice), (1000, 1000, 500));
+        assert_eq!(utils::user_info(seats, bob), (0, 1000, 500));
+        wait(100);
+
+        assert_eq!(forfeit(), vec![U128(500), U128(500)]);
+        assert_eq!(utils::user_info(seats, alice), (1000, 500, 500));
+        assert_eq!(utils::user_info(seats, bob), (0, 500, 500));
+        assert_eq!(utils::user_info(seats, carol), (2500, 0, 0));
+
+        assert_eq!(utils::claim(seats, alice).unwrap_json(), U128(500));
+        assert_eq!(utils::claim(seats, bob).unwrap_json(), U128(500));
+    }
+
+    // a shorter window doesn't forfeit the rewards already given
+    {
+        change_claim_window(Some(ClaimWindow {
+            seconds: 1000,
+            burn: false,
+        }))
+        .assert_success();
+        mint();
+
+        change_claim_window(Some(ClaimWindow {
+            seconds: 10,
+            burn: false,
+        }))
+        .assert_success();
+        wait(100);
+
+        assert_eq!(forfeit(), vec![U128(0), U128(0)]);
+        assert_eq!(utils::claim(seats, alice).unwrap_json(), U128(500));
+        assert_eq!(utils::user_info(seats, alice), (2000, 0, 500));
+    }
+}
+
+/// Rewards from a set only become claimable after the set's unlock date.
+#[test]
+fn claim_unlock_test() {
+    use seats::types::{ClaimBucket, ClaimSource, MintState, Timestamp};
+
+    let (ref root, ref seats, ref alice, ref bob, _) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+    let wait = |blocks: u64| {
+        let mut runtime = root.borrow_runtime_mut();
+        runtime.produce_blocks(blocks).unwrap();
+    };
+    let claimable = |user: &UserAccount| -> U128 {
+        seats
+            .debug_json_view("get_claimable", json!({ "account_id": user.account_id() }))
+            .unwrap_json()
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 050_000_000_000)
+        .assert_success();
+
+    // rewards given before the unlock date was set are claimable right away
+    mint();
+
+    let unlock_date = Timestamp::from(root.borrow_runtime().current_block().block_timestamp)
+        + Timestamp::from_seconds(100);
+    let res = seats.debug_json_call::<Timestamp>(
+        root,
+        "change_vesting_set_claim_unlock",
+        json!({ "name": set_a, "unlock_date": unlock_date }),
+        utils::GAS,
+        parse_near!("0 N"),
+    );
+    assert_eq!(res.unwrap_json(), Timestamp::default());
+
+    // only owners can change the unlock date
+    seats
+        .debug_json_call::<Timestamp>(
+            alice,
+            "change_vesting_set_claim_unlock",
+            json!({ "name": set_a, "unlock_date": Timestamp::default() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_failure_with("The account alice is not a contract owner");
+
+    mint();
+    assert_eq!(utils::user_info(seats, alice), (0, 1000, 500));
+    assert_eq!(claimable(alice), U128(500));
+
+    let buckets: Vec<ClaimBucket> = seats
+        .debug_json_view(
+            "get_claim_buckets",
+            json!({ "account_id": alice.account_id() }),
+        )
+        .unwrap_json();
+    // the rewards with each unlock date are kept apart
+    assert_eq!(buckets.len(), 2);
+    for bucket in &buckets {
+        assert_eq!(
+            bucket.source,
+            ClaimSource::Set {
+                name: set_a.clone(),
+                member: alice.account_id(),
+            }
+        );
+        assert_eq!(bucket.amount, U128(500));
+    }
+    assert_eq!(buckets[0].unlock_at, Timestamp::default());
+    assert_eq!(buckets[1].unlock_at, unlock_date);
+
+    // the locked rewards cannot be claimed yet
+    {
+        assert_eq!(utils::claim(seats, alice).unwrap_json(), U128(500));
+        assert_eq!(utils::user_info(seats, alice), (500, 500, 500));
+
+        seats
+            .debug_json_call::<U128>(
+                alice,
+                "claim_amount",
+                json!({ "amount": U128(1) }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with("cannot claim 1 raw tokens, only 0 are claimable");
+    }
+
+    // after the unlock date, the rewards can be claimed
+    {
+        wait(200);
+        assert_eq!(claimable(alice), U128(500));
+        assert_eq!(utils::claim(seats, alice).unwrap_json(), U128(500));
+        assert_eq!(utils::user_info(seats, alice), (1000, 0, 500));
+
+        // the emptied bucket is kept for the next rewards
+        let buckets: Vec<ClaimBucket> = seats
+            .debug_json_view(
+                "get_claim_buckets",
+                json!({ "account_id": alice.account_id() }),
+            )
+            .unwrap_json();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].amount, U128(0));
+    }
+}
+
+/// The rewards of a member from every generation of a set share a single
+/// bucket while they unlock and expire together, which is reserved when the
+/// member is added.
+#[test]
+fn claim_buckets_test() {
+    use seats::types::{ClaimBucket, ClaimSource, MintState};
+
+    let (ref root, ref seats, ref alice, ref bob, _) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let mint = || {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    };
+    let buckets = |user: &UserAccount| -> Vec<ClaimBucket> {
+        seats
+            .debug_json_view(
+                "get_claim_buckets",
+                json!({ "account_id": user.account_id() }),
+            )
+            .unwrap_json()
+    };
+    let source = |member: &UserAccount| ClaimSource::Set {
+        name: set_a.clone(),
+        member: member.account_id(),
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+
+    // the bucket is paid by the owner that added the member
+    let alice_buckets = buckets(alice);
+    assert_eq!(alice_buckets.len(), 1);
+    assert_eq!(alice_buckets[0].source, source(alice));
+    assert_eq!(alice_buckets[0].amount, U128(0));
+
+    // and by the member that names a receiver
+    seats
+        .debug_json_call::<bool>(
+            alice,
+            "set_vesting_receiver",
+            json!({ "set": set_a, "receiver_id": bob.account_id() }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+        .assert_success();
+    assert_eq!(buckets(bob).len(), 1);
+    assert_eq!(buckets(bob)[0].source, source(alice));
+
+    // the buckets don't grow with the generations
+    for _ in 0..3 {
+        mint();
+    }
+    assert_eq!(buckets(alice).len(), 1);
+    assert_eq!(buckets(bob).len(), 1);
+    assert_eq!(buckets(bob)[0].amount, U128(1500));
+
+    // once claimed and no longer reserved, the buckets are dropped
+    seats
+        .debug_json_call::<bool>(
+            alice,
+            "set_vesting_receiver",
+            json!({ "set": set_a, "receiver_id": null }),
+            utils::GAS,
+            parse_near!("1 N"),
+        )
+        .assert_success();
+    assert_eq!(buckets(bob).len(), 1);
+    assert_eq!(utils::claim(seats, bob).unwrap_json(), U128(1500));
+    assert!(buckets(bob).is_empty());
+}
+
+/// The total supply is broken down into the circulating, claimable, locked
+/// and treasury-held tokens.
+#[test]
+fn supply_breakdown_test() {
+    use seats::types::{MintState, SupplyBreakdown, Timestamp};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let breakdown = || -> SupplyBreakdown {
+        seats
+            .debug_json_view("get_supply_breakdown", json!({}))
+            .unwrap_json()
+    };
+    let text = |method: &str| -> String {
+        let res = seats.json_view::<()>(method, json!({}));
+        String::from_utf8(res.unwrap()).unwrap()
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 050_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 050_000_000_000)
+        .assert_success();
+    seats
+        .debug_json_call::<()>(
+            root,
+            "set_treasury",
+            json!({ "account_id": carol.account_id() }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    let unlock_date = Timestamp::from(root.borrow_runtime().current_block().block_timestamp)
+        + Timestamp::from_seconds(100);
+    seats
+        .debug_json_call::<Timestamp>(
+            root,
+            "change_vesting_set_claim_unlock",
+            json!({ "name": set_a, "unlock_date": unlock_date }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    utils::seat_force_mint(seats, root, alice, 300);
+    utils::seat_force_mint(seats, root, carol, 500);
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != MintState::Standby {}
+
+    assert_eq!(
+        breakdown(),
+        SupplyBreakdown {
+            total_supply: U128(1800),
+            circulating_supply: U128(300),
+            claim_balance: U128(1000),
+            vesting_locked: U128(1000),
+            treasury: U128(500),
+        }
+    );
+
+    // after the unlock date, the claimed tokens start circulating
+    {
+        root.borrow_runtime_mut().produce_blocks(200).unwrap();
+        assert_eq!(utils::claim(seats, bob).unwrap_json(), U128(500));
+
+        assert_eq!(
+            breakdown(),
+            SupplyBreakdown {
+                total_supply: U128(1800),
+                circulating_supply: U128(800),
+                claim_balance: U128(500),
+                vesting_locked: U128(0),
+                treasury: U128(500),
+            }
+        );
+        let circulating: U128 = seats
+            .debug_json_view("ft_circulating_supply", json!({}))
+            .unwrap_json();
+        assert_eq!(circulating, U128(800));
+    }
+
+    // the plain-text values are in whole tokens, and the test metadata has
+    // no decimals
+    assert_eq!(text("get_total_supply_text"), "1800");
+    assert_eq!(text("get_circulating_supply_text"), "800");
+
+    // the locked tokens follow the unlock date that they were given with
+    {
+        let change_unlock = |seconds: u64| {
+            let unlock_date =
+                Timestamp::from(root.borrow_runtime().current_block().block_timestamp)
+                    + Timestamp::from_seconds(seconds);
+            seats
+                .debug_json_call::<Timestamp>(
+                    root,
+                    "change_vesting_set_claim_unlock",
+                    json!({ "name": set_a, "unlock_date": unlock_date }),
+                    utils::GAS,
+                    parse_near!("0 N"),
+                )
+                .assert_success();
+        };
+        let mint = || {
+            utils::start_mint(seats, root).assert_success();
+            while utils::step_mint(seats, root, 100) != MintState::Standby {}
+        };
+
+        // alice's unlocked tokens stay unlocked
+        change_unlock(100);
+        mint();
+        assert_eq!(breakdown().claim_balance, U128(1500));
+        assert_eq!(breakdown().vesting_locked, U128(1000));
+
+        // a later date doesn't re-lock the tokens already given
+        change_unlock(1000);
+        mint();
+        root.borrow_runtime_mut().produce_blocks(200).unwrap();
+        assert_eq!(breakdown().claim_balance, U128(2500));
+        assert_eq!(breakdown().vesting_locked, U128(1000));
+
+        // the tokens that unlocked first are claimed first
+        assert_eq!(
+            seats
+                .debug_json_call::<U128>(
+                    alice,
+                    "claim_amount",
+                    json!({ "amount": U128(700) }),
+                    utils::GAS,
+                    parse_near!("0 N"),
+                )
+                .unwrap_json(),
+            U128(700)
+        );
+        let buckets: Vec<seats::types::ClaimBucket> = seats
+            .debug_json_view(
+                "get_claim_buckets",
+                json!({ "account_id": alice.account_id() }),
+            )
+            .unwrap_json();
+        let amounts: Vec<U128> = buckets.iter().map(|bucket| bucket.amount).collect();
+        assert_eq!(amounts, vec![U128(300), U128(500)]);
+    }
+}
+
+/// The registered accounts can be listed and counted, together with the
+/// accounts that hold tokens.
+#[test]
+fn accounts_registry_test() {
+    use near_sdk::json_types::U64;
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let accounts = |from_index: u64, limit: u16| -> Vec<AccountId> {
+        seats
+            .debug_json_view(
+                "get_accounts",
+                json!({ "from_index": U64(from_index), "limit": limit }),
+            )
+            .unwrap_json()
+    };
+    let accounts_count = || -> u64 {
+        seats
+            .debug_json_view::<U64>("get_accounts_count", json!({}))
+            .unwrap_json()
+            .0
+    };
+    let holders_count = || -> u64 {
+        seats
+            .debug_json_view::<U64>("get_holders_count", json!({}))
+            .unwrap_json()
+            .0
+    };
+
+    let initial = accounts_count();
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    assert_eq!(accounts_count(), initial + 3);
+    assert_eq!(
+        accounts(initial, 10),
+        vec![alice.account_id(), bob.account_id(), carol.account_id()]
+    );
+    assert_eq!(accounts(initial + 1, 1), vec![bob.account_id()]);
+    assert_eq!(holders_count(), 0);
+
+    utils::seat_force_mint(seats, root, alice, 10);
+    assert_eq!(holders_count(), 1);
+
+    // a partial transfer adds a holder
+    utils::transfer(seats, alice, bob, 4).assert_success();
+    assert_eq!(holders_count(), 2);
+
+    // a full transfer keeps the count
+    utils::transfer(seats, alice, carol, 6).assert_success();
+    assert_eq!(holders_count(), 2);
+
+    // unregistering removes the account and the holder
+    utils::seat_unregister_user(seats, bob, Some(true)).assert_success();
+    assert_eq!(accounts_count(), initial + 2);
+    assert_eq!(
+        accounts(initial, 10),
+        vec![alice.account_id(), carol.account_id()]
+    );
+    assert_eq!(holders_count(), 1);
+}
+
+/// Balances and the total supply can be looked up at past times and mint
+/// generations.
+#[test]
+fn checkpoints_test() {
+    use seats::types::{MintState, PastPoint, Timestamp};
+
+    let (ref root, ref seats, ref alice, ref bob, _) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let balance_at = |user: &UserAccount, point: PastPoint| -> u128 {
+        seats
+            .debug_json_view::<U128>(
+                "ft_balance_of_at",
+                json!({ "account_id": user.account_id(), "timestamp_or_generation": point }),
+            )
+            .unwrap_json()
+            .0
+    };
+    let supply_at = |point: PastPoint| -> u128 {
+        seats
+            .debug_json_view::<U128>(
+                "ft_total_supply_at",
+                json!({ "timestamp_or_generation": point }),
+            )
+            .unwrap_json()
+            .0
+    };
+    let now = || Timestamp::from(root.borrow_runtime().current_block().block_timestamp);
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), alice, 100_000_000_000)
+        .assert_success();
+
+    let before = now();
+    root.borrow_runtime_mut().produce_blocks(10).unwrap();
+
+    utils::seat_force_mint(seats, root, alice, 10);
+    let after_force_mint = now();
+    root.borrow_runtime_mut().produce_blocks(10).unwrap();
+
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    let after_mint = now();
+    root.borrow_runtime_mut().produce_blocks(10).unwrap();
+
+    utils::transfer(seats, alice, bob, 400).assert_success();
+
+    // by time
+    assert_eq!(balance_at(alice, PastPoint::Timestamp(before)), 0);
+    assert_eq!(
+        balance_at(alice, PastPoint::Timestamp(after_force_mint)),
+        10
+    );
+    // the transfer replaced the minted balance, which was from the same
+    // generation, so the balance at that time is the one from before the mint
+    assert_eq!(balance_at(alice, PastPoint::Timestamp(after_mint)), 10);
+    assert_eq!(balance_at(alice, PastPoint::Timestamp(now())), 610);
+    assert_eq!(balance_at(bob, PastPoint::Timestamp(after_mint)), 0);
+    assert_eq!(balance_at(bob, PastPoint::Timestamp(now())), 400);
+    assert_eq!(supply_at(PastPoint::Timestamp(before)), 0);
+    assert_eq!(supply_at(PastPoint::Timestamp(after_force_mint)), 10);
+    assert_eq!(supply_at(PastPoint::Timestamp(now())), 1010);
+
+    // by mint generation
+    assert_eq!(balance_at(alice, PastPoint::Generation(0)), 10);
+    assert_eq!(balance_at(alice, PastPoint::Generation(1)), 610);
+    assert_eq!(balance_at(bob, PastPoint::Generation(0)), 0);
+    assert_eq!(supply_at(PastPoint::Generation(0)), 10);
+    assert_eq!(supply_at(PastPoint::Generation(1)), 1010);
+
+    // the history doesn't grow with the transfers of the same generation
+    let storage_usage = || seats.account().unwrap().storage_usage;
+    let before_transfers = storage_usage();
+    for _ in 0..10 {
+        root.borrow_runtime_mut().produce_blocks(1).unwrap();
+        utils::transfer(seats, alice, bob, 10).assert_success();
+        root.borrow_runtime_mut().produce_blocks(1).unwrap();
+        utils::transfer(seats, bob, alice, 10).assert_success();
+    }
+    assert_eq!(storage_usage(), before_transfers);
+    assert_eq!(balance_at(alice, PastPoint::Generation(1)), 610);
+    assert_eq!(balance_at(bob, PastPoint::Generation(1)), 400);
+    assert_eq!(supply_at(PastPoint::Timestamp(now())), 1010);
+
+    // each generation's checkpoint is paid by bob's prepaid history, and once
+    // it's used up the history stops growing
+    let mut growths = vec![];
+    for _ in 0..30 {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+        let before_transfer = storage_usage();
+        utils::transfer(seats, alice, bob, 1).assert_success();
+        growths.push(storage_usage() - before_transfer);
+    }
+    assert!(growths[0] > 0);
+    assert_eq!(growths[growths.len() - 5..], [0; 5]);
+    assert_eq!(balance_at(bob, PastPoint::Generation(2)), 401);
+    assert_eq!(balance_at(bob, PastPoint::Timestamp(now())), 430);
+}
+
+/// Holders delegate their votes, which then follow their balances.
+#[test]
+fn delegation_test() {
+    use seats::types::{MintState, PastPoint, Timestamp};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let delegate = |user: &UserAccount, to: Option<&UserAccount>| {
+        seats.debug_json_call::<()>(
+            user,
+            "delegate",
+            json!({ "to": to.map(|to| to.account_id()) }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+    };
+    let votes = |user: &UserAccount| -> u128 {
+        seats
+            .debug_json_view::<U128>("get_votes", json!({ "account_id": user.account_id() }))
+            .unwrap_json()
+            .0
+    };
+    let past_votes = |user: &UserAccount, timestamp: Timestamp| -> u128 {
+        seats
+            .debug_json_view::<U128>(
+                "get_past_votes",
+                json!({
+                    "account_id": user.account_id(),
+                    "timestamp_or_generation": PastPoint::Timestamp(timestamp),
+                }),
+            )
+            .unwrap_json()
+            .0
+    };
+    let now = || Timestamp::from(root.borrow_runtime().current_block().block_timestamp);
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_force_mint(seats, root, alice, 100);
+
+    // tokens don't count as votes until delegated
+    assert_eq!(votes(alice), 0);
+
+    let res = delegate(alice, Some(bob));
+    res.assert_success();
+    assert!(res.all_logs().contains(
+        &r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"delegate_change","data":[{"delegator_id":"alice","from_delegate_id":null,"to_delegate_id":"bob"}]}"#.to_string()
+    ));
+    assert!(res.all_logs().contains(
+        &r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"delegate_votes_change","data":[{"delegate_id":"bob","previous_votes":"0","new_votes":"100"}]}"#.to_string()
+    ));
+    assert_eq!(votes(bob), 100);
+    assert_eq!(votes(alice), 0);
+    let after_delegation = now();
+    root.borrow_runtime_mut().produce_blocks(10).unwrap();
+
+    // ends the generation, whose last votes are kept in the history
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != MintState::Standby {}
+
+    // the votes follow the transfers and mints
+    utils::transfer(seats, alice, carol, 30).assert_success();
+    utils::seat_force_mint(seats, root, alice, 5);
+    assert_eq!(votes(bob), 75);
+    assert_eq!(votes(carol), 0);
+
+    delegate(carol, Some(carol)).assert_success();
+    assert_eq!(votes(carol), 30);
+
+    // moving the delegation moves the votes
+    delegate(alice, Some(alice)).assert_success();
+    assert_eq!(votes(bob), 0);
+    assert_eq!(votes(alice), 75);
+
+    // stopping the delegation removes the votes
+    delegate(carol, None).assert_success();
+    assert_eq!(votes(carol), 0);
+    utils::transfer(seats, alice, carol, 5).assert_success();
+    assert_eq!(votes(alice), 70);
+    assert_eq!(votes(carol), 0);
+
+    // the past votes are kept
+    assert_eq!(past_votes(bob, after_delegation), 100);
+    assert_eq!(past_votes(alice, after_delegation), 0);
+
+    // the history doesn't grow with the transfers of the same generation
+    let storage_usage = || seats.account().unwrap().storage_usage;
+    let before_transfers = storage_usage();
+    for _ in 0..10 {
+        root.borrow_runtime_mut().produce_blocks(1).unwrap();
+        utils::transfer(seats, alice, carol, 10).assert_success();
+        root.borrow_runtime_mut().produce_blocks(1).unwrap();
+        utils::transfer(seats, carol, alice, 10).assert_success();
+    }
+    assert_eq!(storage_usage(), before_transfers);
+    assert_eq!(votes(alice), 70);
+
+    // each generation's vote checkpoint is paid by the delegate's prepaid
+    // history, and once it's used up the history stops growing
+    delegate(carol, Some(bob)).assert_success();
+    let mut growths = vec![];
+    for _ in 0..30 {
+        utils::start_mint(seats, root).assert_success();
+        while utils::step_mint(seats, root, 100) != MintState::Standby {}
+        let before_transfer = storage_usage();
+        utils::transfer(seats, carol, alice, 1).assert_success();
+        growths.push(storage_usage() - before_transfer);
+    }
+    assert!(growths[0] > 0);
+    assert_eq!(growths[growths.len() - 5..], [0; 5]);
+    assert_eq!(votes(alice), 100);
+    assert_eq!(votes(bob), 5);
+
+    // only registered accounts can delegate
+    seats
+        .debug_json_call::<()>(
+            root,
+            "delegate",
+            json!({ "to": alice.account_id() }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+        .assert_failure_with("account root is not registered");
+}
+
+/// Holders create and vote on proposals, which call whitelisted actions once
+/// approved.
+#[test]
+fn governance_test() {
+    use near_sdk::json_types::U64;
+    use seats::types::{
+        GovernanceConfig, MintState, ProposalAction, ProposalInfo, ProposalStatus, Timestamp, Vote,
+        VoteKind,
+    };
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let propose = |user: &UserAccount, actions: Vec<ProposalAction>| {
+        seats.debug_json_call::<U64>(
+            user,
+            "propose",
+            json!({ "description": "add carol as an owner", "actions": actions }),
+            utils::GAS,
+            parse_near!("0.1 N"),
+        )
+    };
+    let vote = |user: &UserAccount, kind: VoteKind| {
+        seats.debug_json_call::<Vote>(
+            user,
+            "vote",
+            json!({ "proposal_id": U64(0), "kind": kind }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+    };
+    let execute = || {
+        seats.debug_json_call::<()>(
+            bob,
+            "execute_proposal",
+            json!({ "proposal_id": U64(0) }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+    };
+    let proposal = || -> ProposalInfo {
+        seats
+            .debug_json_view::<Option<ProposalInfo>>(
+                "get_proposal",
+                json!({ "proposal_id": U64(0) }),
+            )
+            .unwrap_json()
+            .unwrap()
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_force_mint(seats, root, alice, 600);
+    utils::seat_force_mint(seats, root, bob, 400);
+    for user in [alice, bob] {
+        seats
+            .debug_json_call::<()>(
+                user,
+                "delegate",
+                json!({ "to": user.account_id() }),
+                utils::GAS,
+                parse_near!("0.01 N"),
+            )
+            .assert_success();
+    }
+
+    let actions = vec![
+        ProposalAction::AddOwner {
+            owner_id: carol.account_id(),
+        },
+        ProposalAction::ChangeStartTimestamp {
+            new_start_timestamp_seconds: 10,
+        },
+    ];
+    propose(alice, actions.clone()).assert_failure_with("governance not configured");
+
+    seats
+        .debug_json_call::<Option<GovernanceConfig>>(
+            root,
+            "change_governance_config",
+            json!({ "config": GovernanceConfig {
+                proposal_threshold: U128(500),
+                quorum: U128(500),
+                voting_period_seconds: 100,
+            }}),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // the voting power is taken from a generation that already ended
+    propose(alice, actions.clone())
+        .assert_failure_with("proposals can only be created after the first mint");
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != MintState::Standby {}
+
+    propose(bob, actions.clone())
+        .assert_failure_with("account bob has 400 votes, below the proposal threshold of 500");
+    propose(
+        alice,
+        vec![
+            ProposalAction::AddOwner {
+                owner_id: carol.account_id(),
+            },
+            ProposalAction::RemoveOwner {
+                owner_id: root.account_id(),
+            },
+            ProposalAction::RemoveOwner {
+                owner_id: carol.account_id(),
+            },
+        ],
+    )
+    .assert_failure_with("removing owner carol would leave no owners");
+    assert_eq!(propose(alice, actions).unwrap_json(), U64(0));
+    assert_eq!(proposal().status, ProposalStatus::Active);
+
+    // the transfers after the snapshot don't change the voting power
+    utils::transfer(seats, alice, bob, 100).assert_success();
+    assert_eq!(
+        vote(alice, VoteKind::For).unwrap_json(),
+        Vote {
+            kind: VoteKind::For,
+            weight: U128(600)
+        }
+    );
+    vote(alice, VoteKind::Against).assert_failure_with("account alice already voted on proposal 0");
+    vote(bob, VoteKind::Against).assert_success();
+    vote(carol, VoteKind::For)
+        .assert_failure_with("account carol had no votes at the snapshot of proposal 0");
+
+    // can only be executed after the voting period
+    execute().assert_failure_with("proposal 0 has not succeeded");
+    root.borrow_runtime_mut().produce_blocks(200).unwrap();
+    assert_eq!(proposal().status, ProposalStatus::Succeeded);
+    vote(carol, VoteKind::For).assert_failure_with("proposal 0 is not active");
+
+    let info = proposal();
+    assert_eq!(info.proposal.votes_for, U128(600));
+    assert_eq!(info.proposal.votes_against, U128(400));
+
+    execute().assert_success();
+    assert_eq!(proposal().status, ProposalStatus::Executed);
+    execute().assert_failure_with("proposal 0 has not succeeded");
+
+    let is_owner: bool = seats
+        .debug_json_view("is_owner", json!({ "owner_id": carol.account_id() }))
+        .unwrap_json();
+    assert!(is_owner);
+    let start_timestamp: Timestamp = seats
+        .debug_json_view("get_start_timestamp", json!({}))
+        .unwrap_json();
+    assert_eq!(start_timestamp, Timestamp::from_seconds(10));
+}
+
+/// Stakers share the per-mint reward of the staking set, pro rata to their
+/// stakes, and unstaked tokens are held for the cooldown.
+#[test]
+fn staking_test() {
+    use seats::types::{MintState, Stake, StakingConfig};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+    let stake = |user: &UserAccount, amount: u128| {
+        seats.debug_json_call::<Stake>(
+            user,
+            "stake",
+            json!({ "amount": U128(amount) }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+    };
+    let get_stake = |user: &UserAccount| -> Stake {
+        seats
+            .debug_json_view::<Option<Stake>>(
+                "get_stake",
+                json!({ "account_id": user.account_id() }),
+            )
+            .unwrap_json()
+            .unwrap()
+    };
+    let total_staked = || -> U128 {
+        seats
+            .debug_json_view("get_total_staked", json!({}))
+            .unwrap_json()
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), carol, 050_000_000_000)
+        .assert_success();
+    utils::seat_force_mint(seats, root, alice, 300);
+    utils::seat_force_mint(seats, root, bob, 100);
+
+    seats
+        .debug_json_call::<StakingConfig>(
+            root,
+            "change_staking_config",
+            json!({ "config": StakingConfig {
+                reward_set: Some(set_a.clone()),
+                cooldown_seconds: 50,
+            }}),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    stake(alice, 300).assert_success();
+    stake(bob, 100).assert_success();
+    stake(bob, 1).assert_failure_with("The account doesn't have enough balance");
+    assert_eq!(total_staked(), U128(400));
+    assert_eq!(utils::user_info(seats, alice).0, 0);
+    assert_eq!(utils::total_supply(seats), 400);
+
+    // the set rewards the stakers instead of it's members
+    utils::start_mint(seats, root).assert_success();
+    while utils::step_mint(seats, root, 100) != MintState::Standby {}
+    assert_eq!(get_stake(alice).rewards, U128(750));
+    assert_eq!(get_stake(bob).rewards, U128(250));
+    assert_eq!(utils::user_info(seats, carol).0, 0);
+    assert_eq!(utils::total_supply(seats), 400);
+
+    let claimed = seats
+        .debug_json_call::<U128>(
+            alice,
+            "claim_staking_rewards",
+            json!({}),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .unwrap_json();
+    assert_eq!(claimed, U128(750));
+    assert_eq!(utils::user_info(seats, alice).0, 750);
+    assert_eq!(get_stake(alice).rewards, U128(0));
+    assert_eq!(utils::total_supply(seats), 1150);
+
+    // unstaked tokens are held for the cooldown
+    {
+        let unstaked = seats
+            .debug_json_call::<Stake>(
+                bob,
+                "unstake",
+                json!({ "amount": U128(100) }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(unstaked.staked, U128(0));
+        assert_eq!(unstaked.unstaking, U128(100));
+        assert_eq!(total_staked(), U128(300));
+
+        let withdraw = || {
+            seats.debug_json_call::<U128>(
+                bob,
+                "withdraw_unstaked",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+        };
+        withdraw().assert_failure_with("the unstaked tokens are held for");
+        root.borrow_runtime_mut().produce_blocks(100).unwrap();
+        assert_eq!(withdraw().unwrap_json(), U128(100));
+        assert_eq!(utils::user_info(seats, bob).0, 100);
+    }
+
+    // forcing the unregistration burns the stake
+    utils::seat_unregister_user(seats, alice, Some(true)).assert_success();
+    assert_eq!(total_staked(), U128(0));
+    assert_eq!(utils::total_supply(seats), 100);
+}
+
+/// A sender streams tokens to a receiver, who withdraws them as they are
+/// streamed, and a cancellation splits the remaining ones.
+#[test]
+fn streams_test() {
+    use near_sdk::json_types::U64;
+    use seats::types::{StreamInfo, StreamStatus, Timestamp};
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+
+    let stream = || -> StreamInfo {
+        seats
+            .debug_json_view::<Option<StreamInfo>>("get_stream", json!({ "stream_id": U64(0) }))
+            .unwrap_json()
+            .unwrap()
+    };
+    let withdraw = || {
+        seats.debug_json_call::<U128>(
+            bob,
+            "withdraw_from_stream",
+            json!({ "stream_id": U64(0) }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+    };
+    let cancel = |user: &UserAccount| {
+        seats.debug_json_call::<StreamInfo>(
+            user,
+            "cancel_stream",
+            json!({ "stream_id": U64(0) }),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+    };
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_force_mint(seats, root, alice, 1000);
+
+    let now = Timestamp::from(root.borrow_runtime().current_block().block_timestamp);
+    let start = now + Timestamp::from_seconds(10);
+    let stop = start + Timestamp::from_seconds(100);
+    let create = |start: Timestamp| {
+        seats.debug_json_call::<U64>(
+            alice,
+            "create_stream",
+            json!({
+                "receiver_id": bob.account_id(),
+                "rate_per_second": U128(5),
+                "start": start,
+                "stop": stop,
+            }),
+            utils::GAS,
+            parse_near!("0.01 N"),
+        )
+    };
+    create(now).assert_failure_with("the stream cannot start in the past");
+    let res = create(start);
+    assert_eq!(res.unwrap_json(), U64(0));
+    // the events report the streamed tokens as burned until released
+    assert!(res.all_logs().contains(
+        &r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":[{"owner_id":"alice","amount":"500","memo":"stream"}]}"#.to_string()
+    ));
+
+    // the streamed tokens are held by the stream
+    assert_eq!(stream().status, StreamStatus::Pending);
+    assert_eq!(stream().stream.deposit, U128(500));
+    assert_eq!(utils::user_info(seats, alice).0, 500);
+    assert_eq!(utils::total_supply(seats), 1000);
+    withdraw().assert_failure_with("stream 0 has no tokens to withdraw");
+    cancel(carol)
+        .assert_failure_with("account carol is neither the sender nor the receiver of stream 0");
+
+    // the receiver withdraws what was streamed so far
+    root.borrow_runtime_mut().produce_blocks(50).unwrap();
+    assert_eq!(stream().status, StreamStatus::Streaming);
+    assert!(stream().withdrawable.0 > 0);
+    let res = withdraw();
+    let withdrawn = res.unwrap_json();
+    assert!(withdrawn.0 > 0 && withdrawn.0 < 500);
+    assert!(res.all_logs().contains(
+        &format!(
+            r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{{"owner_id":"bob","amount":"{}","memo":"stream"}}]}}"#,
+            withdrawn.0
+        )
+    ));
+    assert_eq!(utils::user_info(seats, bob).0, withdrawn.0);
+    assert_eq!(stream().stream.withdrawn, withdrawn);
+
+    // the cancellation splits the remaining tokens
+    root.borrow_runtime_mut().produce_blocks(10).unwrap();
+    let canceled = cancel(alice).unwrap_json();
+    assert_eq!(canceled.status, StreamStatus::Canceled);
+    assert_eq!(canceled.withdrawable, U128(0));
+    let (alice_balance, bob_balance) = (
+        utils::user_info(seats, alice).0,
+        utils::user_info(seats, bob).0,
+    );
+    assert!(bob_balance > withdrawn.0);
+    assert_eq!(alice_balance + bob_balance, 1000);
+    assert_eq!(utils::total_supply(seats), 1000);
+    cancel(alice).assert_failure_with("stream 0 cannot be canceled, it's status is Canceled");
+}
+
+/// Exports the state from a contract and imports it into another one.
+#[test]
+fn relocation_test() {
+    use seats::types::{
+        ExportedAccount, ExportedMintInfo, ExportedVestingMember, MetadataVersion, StreamInfo,
+        Timestamp, VestingUserSetInfo,
+    };
+
+    let (ref root, ref seats, ref alice, ref bob, ref carol) = init(0, 0, None, None, 0);
+    let seats2 = &utils::setup_seats(root, "seats2", 0, 0, None, None, 0);
+
+    let set_a = &SetName::new("set-a".to_string());
+
+    utils::seat_register_user(seats, alice);
+    utils::seat_register_user(seats, bob);
+    utils::seat_register_user(seats, carol);
+    utils::seat_force_mint(seats, root, alice, 10);
+    utils::seat_force_mint(seats, root, bob, 5);
+
+    utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, true).assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), bob, 030_000_000_000)
+        .assert_success();
+    utils::seat_register_vesting_user(seats, root, set_a.clone(), carol, 070_000_000_000)
+        .assert_success();
+    let call = |user: &UserAccount,
+                method: &str,
+                args: near_sdk::serde_json::Value,
+                deposit: u128| {
+        seats
+            .debug_json_call::<near_sdk::serde_json::Value>(user, method, args, utils::GAS, deposit)
+            .assert_success()
+    };
+    call(
+        root,
+        "set_treasury",
+        json!({ "account_id": carol.account_id() }),
+        0,
+    );
+    call(
+        bob,
+        "delegate",
+        json!({ "to": alice.account_id() }),
+        parse_near!("1 N"),
+    );
+    call(
+        bob,
+        "set_vesting_receiver",
+        json!({ "set": set_a, "receiver_id": alice.account_id() }),
+        parse_near!("1 N"),
+    );
+    call(
+        root,
+        "set_metadata_name",
+        json!({ "name": "SEAT" }),
+        parse_near!("1 N"),
+    );
+    call(
+        root,
+        "change_staking_config",
+        json!({ "config": { "reward_set": set_a, "cooldown_seconds": 10 } }),
+        0,
+    );
+    call(alice, "stake", json!({ "amount": "4" }), parse_near!("1 N"));
+    let start = Timestamp::from(root.borrow_runtime().current_block().block_timestamp)
+        + Timestamp::from_seconds(10);
+    call(
+        alice,
+        "create_stream",
+        json!({
+            "receiver_id": bob.account_id(),
+            "rate_per_second": "1",
+            "start": start,
+            "stop": start + Timestamp::from_seconds(3),
+        }),
+        parse_near!("1 N"),
+    );
+
+    // the new contract pays for the import progress, while the imported
+    // state is charged from the import calls
+    root.transfer(seats2.account_id(), parse_near!("1 N"));
+
+    // starts the import
+    {
+        let mint_info: ExportedMintInfo = seats
+            .debug_json_view("export_mint_info", json!({}))
+            .unwrap_json();
+        assert_eq!(mint_info.total_supply.0, 15);
+
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "start_import",
+                json!({ "mint_info": mint_info }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+    }
+
+    // imports the accounts, in pages of two
+    {
+        let mut from_index = 0;
+        loop {
+            let accounts: Vec<ExportedAccount> = seats
+                .debug_json_view(
+                    "export_accounts",
+                    json!({ "from_index": U64(from_index), "limit": 2 }),
+                )
+                .unwrap_json();
+            if accounts.is_empty() {
+                break;
+            }
+            from_index += accounts.len() as u64;
+
+            // the import can't be finished while there are missing accounts
+            seats2
+                .debug_json_call::<()>(
+                    root,
+                    "finish_import",
+                    json!({}),
+                    utils::GAS,
+                    parse_near!("0 N"),
+                )
+                .assert_failure_with("but the total supply is 15");
+
+            seats2
+                .debug_json_call::<()>(
+                    root,
+                    "import_accounts",
+                    json!({ "accounts": accounts }),
+                    utils::GAS,
+                    parse_near!("1 N"),
+                )
+                .assert_success();
+        }
+        // root, alice, bob and carol
+        assert_eq!(from_index, 4);
+    }
+
+    // imports the vesting sets and their members
+    {
+        let sets: Vec<(SetName, VestingUserSetInfo)> = seats
+            .debug_json_view("export_vesting_sets", json!({}))
+            .unwrap_json();
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "import_vesting_sets",
+                json!({ "sets": sets }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+
+        let members: Vec<ExportedVestingMember> = seats
+            .debug_json_view("export_vesting_members", json!({ "set": set_a }))
+            .unwrap_json();
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "import_vesting_members",
+                json!({ "set": set_a, "members": members }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+    }
+
+    // imports the streams
+    {
+        let streams: Vec<StreamInfo> = seats
+            .debug_json_view("get_streams", json!({}))
+            .unwrap_json();
+        assert_eq!(streams.len(), 1);
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "import_streams",
+                json!({ "streams": streams }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "import_streams",
+                json!({ "streams": streams }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_failure_with("stream 0 is out of order, the next one is 1");
+    }
+
+    // imports the metadata history
+    {
+        let versions: Vec<MetadataVersion> = seats
+            .debug_json_view("get_metadata_history", json!({}))
+            .unwrap_json();
+        assert_eq!(versions.len(), 1);
+        seats2
+            .debug_json_call::<()>(
+                root,
+                "import_metadata_history",
+                json!({ "versions": versions }),
+                utils::GAS,
+                parse_near!("1 N"),
+            )
+            .assert_success();
+    }
+
+    seats2
+        .debug_json_call::<()>(
+            root,
+            "finish_import",
+            json!({}),
+            utils::GAS,
+            parse_near!("0 N"),
+        )
+        .assert_success();
+
+    // the state is the same
+    {
+        assert_eq!(utils::total_supply(seats2), 15);
+        assert_eq!(
+            utils::user_info(seats2, alice),
+            utils::user_info(seats, alice)
+        );
+        assert_eq!(utils::user_info(seats2, bob), utils::user_info(seats, bob));
+        assert_eq!(
+            utils::user_info(seats2, carol),
+            utils::user_info(seats, carol)
+        );
+        assert_eq!(
+            utils::seat_get_set(seats2, set_a).unwrap_json(),
+            utils::seat_get_set(seats, set_a).unwrap_json()
+        );
+
+        let view = |seats: &ContractAccount<SeatsContract>,
+                    method: &str,
+                    args: near_sdk::serde_json::Value| {
+            seats
+                .debug_json_view::<near_sdk::serde_json::Value>(method, args)
+                .unwrap_json()
+        };
+        for (method, args) in [
+            ("get_treasury", json!({})),
+            ("get_delegate", json!({ "account_id": bob.account_id() })),
+            ("get_votes", json!({ "account_id": alice.account_id() })),
+            (
+                "get_vesting_receiver",
+                json!({ "set": set_a, "account_id": bob.account_id() }),
+            ),
+            ("get_metadata_history", json!({})),
+            ("get_staking_config", json!({})),
+            ("get_total_staked", json!({})),
+            ("get_stake", json!({ "account_id": alice.account_id() })),
+            ("get_streams", json!({})),
+        ] {
+            assert_eq!(
+                view(seats2, method, args.clone()),
+                view(seats, method, args)
+            );
+        }
+
+        utils::transfer(seats2, alice, bob, 1).assert_success();
+        assert_eq!(utils::user_info(seats2, bob), (6, 0, 300));
+    }
+}
+
+/// Deploys the contract from it's first release, upgrades it's code and
+/// migrates it's state.
+///
+/// This test is ignored by default because it requires the wasm file from the
+/// first release to be stored at `res/stlb_seats_ft_v0.wasm`, which is the
+/// `res/stlb_seats_ft.wasm` built by `build.sh` from that release.
+#[ignore]
+#[test]
+fn migration_from_v0_test() {
+    use seats::types::{AccountsMigration, MigrationState};
+
+    let root = &init_simulator(None);
+    let seats = &utils::setup_seats_v0(root, "seats", 0, 0);
+    let alice = &root.create_user("alice".parse().unwrap(), parse_near!("10 kN"));
+    let bob = &root.create_user("bob".parse().unwrap(), parse_near!("10 kN"));
+
+    let set_a = &SetName::new("set-a".to_string());
+
+    // creates some state on the old contract
+    {
+        utils::seat_register_user(seats, alice);
+        utils::seat_register_user(seats, bob);
+        utils::seat_force_mint(seats, root, alice, 10);
+
+        let res = utils::seat_register_set(seats, root, set_a.clone(), 0, u32::MAX, 1000, false);
+        res.assert_success();
+
+        let res = utils::seat_register_vesting_user(
+            seats,
+            root,
+            set_a.clone(),
+            alice,
+            // 50%
+            050_000_000_000,
+        );
+        res.assert_success();
+
+        let res = utils::seat_register_vesting_user(
+            seats,
+            root,
+            set_a.clone(),
+            bob,
+            // 50%
+            050_000_000_000,
+        );
+        res.assert_success();
+    }
+
+    // upgrades the contract
+    {
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_start_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+
+        utils::seat_deploy_and_migrate(seats).assert_success();
+
+        let version: u16 = seats
+            .debug_json_view("get_state_version", json!({}))
+            .unwrap_json();
+        assert_eq!(version, 1);
+
+        // the contract is still locked
+        utils::transfer(seats, alice, bob, 1)
+            .assert_failure_with("the contract is locked for migration");
+
+        // the vesting sets were converted right away, and the members kept
+        // their layout
+        let state: MigrationState = seats
+            .debug_json_view("get_migration_state", json!({}))
+            .unwrap_json();
+        assert_eq!(state, MigrationState::Standby);
+    }
+
+    // migrates the accounts
+    {
+        // can't be unlocked before the accounts holding tokens are migrated
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_end_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_failure_with(
+                "10 raw tokens are still in accounts not converted by migrate_accounts",
+            );
+
+        let converted: u32 = seats
+            .debug_json_call(
+                root,
+                "migrate_accounts",
+                json!({
+                    "account_ids": [root.account_id(), alice.account_id(), bob.account_id()]
+                }),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .unwrap_json();
+        assert_eq!(converted, 3);
+        let progress: AccountsMigration = seats
+            .debug_json_view("get_accounts_migration", json!({}))
+            .unwrap_json();
+        assert_eq!(
+            progress,
+            AccountsMigration {
+                converted_accounts: 3.into(),
+                unconverted_supply: 0.into(),
+            }
+        );
+
+        seats
+            .debug_json_call::<()>(
+                root,
+                "force_end_migration",
+                json!({}),
+                utils::GAS,
+                parse_near!("0 N"),
+            )
+            .assert_success();
+    }
+
+    // the previous state is kept
+    {
+        assert_eq!(utils::user_info(seats, alice), (10, 0, 500));
+        assert_eq!(utils::user_info(seats, bob), (0, 0, 500));
+        assert_eq!(utils::total_supply(seats), 10);
+
+        let set_a_info = utils::seat_get_set(seats, set_a).unwrap_json().unwrap();
+        assert_eq!(set_a_info.total_user_percentages.0, 100_000_000_000);
+
+        utils::transfer(seats, alice, bob, 1).assert_success();
+        assert_eq!(utils::user_info(seats, bob), (1, 0, 500));
+    }
+}
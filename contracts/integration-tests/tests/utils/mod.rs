@@ -8,7 +8,7 @@ use near_sdk::{
     serde_json::json,
     AccountId, Gas,
 };
-use near_sdk_sim::{ContractAccount, UserAccount};
+use near_sdk_sim::{ContractAccount, ExecutionResult, UserAccount};
 use near_units::{parse_gas, parse_near};
 use seats::types::MintState;
 use seats::types::SetName;
@@ -138,6 +138,116 @@ pub fn setup_seats_default(
     seats
 }
 
+/// Deploys the contract in it's setup phase, by `new_staged`.
+pub fn setup_seats_staged(
+    root: &UserAccount,
+    contract_id: &str,
+    mint_lock_duration_seconds: u32,
+    start_timestamp_seconds: u32,
+) -> ContractAccount<SeatsContract> {
+    let seats = ContractAccount::<SeatsContract>::debug_json_deploy(
+        root,
+        contract_id,
+        &SEATS_WASM_BYTES,
+        "new_staged",
+        json!({
+            "owner_id": root.account_id(),
+            "metadata": seats::constant_init::metadata(),
+            "mint_lock_duration_seconds": mint_lock_duration_seconds,
+            "start_timestamp_seconds": start_timestamp_seconds
+        }),
+        GAS,
+        seats_costs::CONTRACT,
+    );
+
+    seats.transfer_extra_deposit_to(root);
+    seats
+}
+
+/// Calls `import_set_members`, attaching 1 N which gets partially refunded.
+pub fn seat_import_set_members(
+    seats: &ContractAccount<SeatsContract>,
+    caller: &UserAccount,
+    set_members: Vec<seats::init::SetMembers>,
+) -> Execution<()> {
+    seats.debug_json_call(
+        caller,
+        "import_set_members",
+        json!({ "set_members": set_members }),
+        GAS,
+        parse_near!("1 N"),
+    )
+}
+
+/// Deploys the contract from it's first release, which had no state versioning.
+///
+/// The wasm file must be stored at `res/stlb_seats_ft_v0.wasm`.
+pub fn setup_seats_v0(
+    root: &UserAccount,
+    contract_id: &str,
+    mint_lock_duration_seconds: u32,
+    start_timestamp_seconds: u32,
+) -> ContractAccount<SeatsContract> {
+    let wasm_bytes = std::fs::read("../res/stlb_seats_ft_v0.wasm")
+        .expect("missing the wasm file at res/stlb_seats_ft_v0.wasm");
+
+    let seats = ContractAccount::<SeatsContract>::debug_json_deploy(
+        root,
+        contract_id,
+        &wasm_bytes,
+        "new",
+        json!({
+            "owner_id": root.account_id(),
+            "metadata": seats::constant_init::metadata(),
+            "mint_lock_duration_seconds": mint_lock_duration_seconds,
+            "start_timestamp_seconds": start_timestamp_seconds
+        }),
+        GAS,
+        seats_costs::CONTRACT,
+    );
+
+    seats.transfer_extra_deposit_to(root);
+    seats
+}
+
+/// Deploys the current contract code into the existing contract account, and
+/// then migrates it's state.
+///
+/// This uses the contract account's own full-access key.
+pub fn seat_deploy_and_migrate(seats: &ContractAccount<SeatsContract>) -> ExecutionResult {
+    seats
+        .user_account
+        .create_transaction(seats.account_id())
+        .deploy_contract(SEATS_WASM_BYTES.to_vec())
+        .function_call("migrate".to_string(), b"{}".to_vec(), GAS.0, 0)
+        .submit()
+}
+
+/// Calls `upgrade` with the given wasm code, or with the current contract
+/// code if none is given.
+pub fn seat_upgrade(
+    seats: &ContractAccount<SeatsContract>,
+    caller: &UserAccount,
+    code: Option<&[u8]>,
+) -> ExecutionResult {
+    let code = code.unwrap_or(&SEATS_WASM_BYTES);
+    caller.call(seats.account_id(), "upgrade", code, GAS.0, 0)
+}
+
+/// Calls `stage_code` with the current contract code.
+pub fn seat_stage_code(
+    seats: &ContractAccount<SeatsContract>,
+    caller: &UserAccount,
+) -> ExecutionResult {
+    caller.call(
+        seats.account_id(),
+        "stage_code",
+        &SEATS_WASM_BYTES,
+        GAS.0,
+        parse_near!("10 N"),
+    )
+}
+
 pub fn seat_register_set(
     seats: &ContractAccount<SeatsContract>,
     root: &UserAccount,
@@ -196,13 +306,10 @@ pub fn seat_register_user(
             seats_costs::user::BALANCE_REQUIREMENT,
         )
         .unwrap_json();
-    assert_eq!(
-        balance_status,
-        StorageBalance {
-            total: seats_costs::user::BALANCE_REQUIREMENT.into(),
-            available: 0.into()
-        }
-    );
+    // only the storage actually used is charged
+    assert!(balance_status.total.0 > 0);
+    assert!(balance_status.total.0 <= seats_costs::user::BALANCE_REQUIREMENT);
+    assert_eq!(balance_status.available, 0.into());
 }
 
 pub fn seat_unregister_user(
@@ -267,8 +374,18 @@ pub fn seat_force_mint(
     user: &UserAccount,
     amount: u128,
 ) {
-    let res = seats.debug_json_call(
-        root,
+    let res = seat_try_force_mint(seats, root, user, amount);
+    res.assert_success();
+}
+
+pub fn seat_try_force_mint(
+    seats: &ContractAccount<SeatsContract>,
+    owner: &UserAccount,
+    user: &UserAccount,
+    amount: u128,
+) -> Execution<()> {
+    seats.debug_json_call(
+        owner,
         "force_mint",
         json!({
             "account_id": user.account_id(),
@@ -276,8 +393,55 @@ pub fn seat_force_mint(
         }),
         GAS,
         parse_near!("0 N"),
-    );
-    res.assert_success();
+    )
+}
+
+pub fn seat_change_mint_budget(
+    seats: &ContractAccount<SeatsContract>,
+    root: &UserAccount,
+    window_seconds: u32,
+    global_limit: Option<u128>,
+    owner_limit: Option<u128>,
+) -> Execution<Option<seats::types::PendingMintBudget>> {
+    seats.debug_json_call(
+        root,
+        "change_mint_budget",
+        json!({
+            "window_seconds": window_seconds,
+            "global_limit": global_limit.map(seats::types::RewardAmount),
+            "owner_limit": owner_limit.map(seats::types::RewardAmount),
+        }),
+        GAS,
+        parse_near!("0 N"),
+    )
+}
+
+pub fn seat_pause(
+    seats: &ContractAccount<SeatsContract>,
+    caller: &UserAccount,
+    features: &[seats::types::Feature],
+) -> Execution<()> {
+    seats.debug_json_call(
+        caller,
+        "pause",
+        json!({ "features": features }),
+        GAS,
+        parse_near!("0 N"),
+    )
+}
+
+pub fn seat_unpause(
+    seats: &ContractAccount<SeatsContract>,
+    caller: &UserAccount,
+    features: &[seats::types::Feature],
+) -> Execution<()> {
+    seats.debug_json_call(
+        caller,
+        "unpause",
+        json!({ "features": features }),
+        GAS,
+        parse_near!("0 N"),
+    )
 }
 
 pub fn user_info(
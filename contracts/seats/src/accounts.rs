@@ -0,0 +1,42 @@
+//! Enumeration of the registered accounts.
+
+use crate::Seats;
+use near_sdk::{json_types::U64, near_bindgen, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Get the registered accounts.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many accounts to skip.
+    /// - `limit`: How many accounts to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn get_accounts(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<AccountId> {
+        let account_ids = self.account_ids.as_vector();
+        crate::page_range(from_index, limit, account_ids.len())
+            .filter_map(|index| account_ids.get(index))
+            .collect()
+    }
+
+    /// Get how many accounts are registered.
+    pub fn get_accounts_count(&self) -> U64 {
+        self.account_ids.len().into()
+    }
+
+    /// Get how many registered accounts have a positive balance.
+    ///
+    /// ###### Notes
+    ///
+    /// - Tokens that must still be claimed are not counted.
+    pub fn get_holders_count(&self) -> U64 {
+        self.holders.into()
+    }
+}
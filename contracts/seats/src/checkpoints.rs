@@ -0,0 +1,194 @@
+//! History of the balances and of the total supply, so they can be looked up
+//! at a past time or mint generation.
+//!
+//! Only the last value of each mint generation is kept, so that the history
+//! grows by at most one checkpoint per account on each mint, however many
+//! transfers the account makes.
+//!
+//! That growth is paid by the account's prepaid history, kept from it's
+//! registration deposit. Once it's used up, or for accounts that didn't pay
+//! for it, each new checkpoint replaces the account's last one instead.
+
+use crate::{storage_costs, types, Seats, StorageKey};
+use near_sdk::{collections::Vector, env, json_types::U128, near_bindgen, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Get the balance that an account had at a past point.
+    ///
+    /// ### Parameters
+    ///
+    /// - `account_id`: The account.
+    /// - `timestamp_or_generation`: Either a timestamp or a mint generation,
+    /// such as `{"t": "Generation", "c": 3}`.
+    ///
+    /// ###### Notes
+    ///
+    /// - Balances from before an account got registered, or from before the
+    /// history was recorded, are `0`.
+    /// - Since only the last balance of each mint generation is kept, a
+    /// timestamp between two changes of the same generation gets the balance
+    /// from the end of the previous generation.
+    /// - Once the account's prepaid history is used up, the generations
+    /// whose checkpoints got replaced get an older balance.
+    pub fn ft_balance_of_at(
+        &self,
+        account_id: AccountId,
+        timestamp_or_generation: types::PastPoint,
+    ) -> U128 {
+        self.checkpoints
+            .accounts
+            .get(&account_id)
+            .map(|checkpoints| value_at(&checkpoints, timestamp_or_generation))
+            .unwrap_or_default()
+            .into()
+    }
+
+    /// Get the total supply at a past point.
+    ///
+    /// ### Parameters
+    ///
+    /// - `timestamp_or_generation`: Either a timestamp or a mint generation,
+    /// such as `{"t": "Generation", "c": 3}`.
+    ///
+    /// ###### Notes
+    ///
+    /// - Since only the last total supply of each mint generation is kept, a
+    /// timestamp between two changes of the same generation gets the total
+    /// supply from the end of the previous generation.
+    pub fn ft_total_supply_at(
+        &self,
+        timestamp_or_generation: types::PastPoint,
+    ) -> U128 {
+        value_at(&self.checkpoints.total_supply, timestamp_or_generation).into()
+    }
+}
+
+impl Seats {
+    /// Records the current balance of an account.
+    pub fn internal_checkpoint_balance(
+        &mut self,
+        account_id: &AccountId,
+        balance: Balance,
+    ) {
+        let generation = self.internal_checkpoint_generation();
+        self.internal_charge_history(account_id, |this, can_grow| {
+            let mut checkpoints = this
+                .checkpoints
+                .accounts
+                .get(account_id)
+                .unwrap_or_else(|| {
+                    Vector::new(StorageKey::AccountCheckpoints {
+                        account_id: account_id.clone(),
+                    })
+                });
+            push_checkpoint(&mut checkpoints, generation, balance, can_grow);
+            this.checkpoints.accounts.insert(account_id, &checkpoints);
+        });
+    }
+
+    /// Changes the balance or vote history of an account, paying for it's
+    /// storage growth with the account's prepaid history.
+    ///
+    /// The change may only add checkpoints while the prepaid history still
+    /// covers a [`CHECKPOINT`](storage_costs::user::CHECKPOINT).
+    pub fn internal_charge_history(
+        &mut self,
+        account_id: &AccountId,
+        change: impl FnOnce(&mut Self, bool),
+    ) {
+        let allowance = self
+            .checkpoints
+            .allowances
+            .get(account_id)
+            .unwrap_or_default();
+
+        let initial_storage_usage = env::storage_usage();
+        change(self, allowance >= storage_costs::user::CHECKPOINT);
+        let used_storage = env::storage_usage().saturating_sub(initial_storage_usage);
+
+        if used_storage > 0 && allowance > 0 {
+            let used_cost = env::storage_byte_cost() * Balance::from(used_storage);
+            self.checkpoints
+                .allowances
+                .insert(account_id, &allowance.saturating_sub(used_cost));
+        }
+    }
+
+    /// Records the current total supply.
+    pub fn internal_checkpoint_supply(&mut self) {
+        let generation = self.internal_checkpoint_generation();
+        push_checkpoint(
+            &mut self.checkpoints.total_supply,
+            generation,
+            self.total_supply,
+            true,
+        );
+    }
+
+    /// The generation of new checkpoints, which is the one being minted if
+    /// a mint is in progress.
+    pub fn internal_checkpoint_generation(&self) -> u32 {
+        if self.is_on_minting() {
+            self.mint.generation + 1
+        } else {
+            self.mint.generation
+        }
+    }
+}
+
+/// Adds a checkpoint, replacing the last one if it's from the same mint
+/// generation or if the history can't grow, or does nothing if the value
+/// didn't change.
+pub fn push_checkpoint(
+    checkpoints: &mut Vector<types::Checkpoint>,
+    generation: u32,
+    value: Balance,
+    can_grow: bool,
+) {
+    let checkpoint = types::Checkpoint {
+        timestamp: types::Timestamp::from(env::block_timestamp()),
+        generation,
+        value: value.into(),
+    };
+
+    let last = checkpoints
+        .len()
+        .checked_sub(1)
+        .and_then(|last_index| Some((last_index, checkpoints.get(last_index)?)));
+    match last {
+        Some((_, last)) if last.value == checkpoint.value => {}
+        Some((last_index, last)) if last.generation == generation || !can_grow => {
+            checkpoints.replace(last_index, &checkpoint);
+        }
+        _ => {
+            checkpoints.push(&checkpoint);
+        }
+    }
+}
+
+/// Binary searches for the last checkpoint at or before the point, returning
+/// it's value, or `0` if there is none.
+pub fn value_at(
+    checkpoints: &Vector<types::Checkpoint>,
+    point: types::PastPoint,
+) -> Balance {
+    // how many checkpoints are at or before the point
+    let (mut low, mut high) = (0, checkpoints.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if point.includes(&checkpoints.get(mid).unwrap()) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    match low.checked_sub(1) {
+        Some(index) => checkpoints.get(index).unwrap().value.0,
+        None => 0,
+    }
+}
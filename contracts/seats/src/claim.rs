@@ -1,23 +1,261 @@
-use crate::{fungible_token::events, Seats};
-use near_sdk::{env, json_types::U128, near_bindgen};
+use crate::{events as seat_events, fungible_token::events, types, Seats};
+use near_sdk::{env, json_types::U128, near_bindgen, require, AccountId, PromiseOrValue};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::SeatsContract;
 
 #[near_bindgen]
 impl Seats {
+    /// Moves every claimable token of the caller into it's balance.
+    ///
+    /// Returns how many raw SEAT tokens got claimed.
     pub fn claim(&mut self) -> U128 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+
+        let predecessor = env::predecessor_account_id();
+        self.internal_claim(&predecessor, None).into()
+    }
+
+    /// Moves some claimable tokens of the caller into it's balance.
+    ///
+    /// ### Parameters
+    ///
+    /// - `amount`: How many raw SEAT tokens to claim.
+    ///
+    /// Returns how many raw SEAT tokens got claimed.
+    pub fn claim_amount(
+        &mut self,
+        amount: U128,
+    ) -> U128 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+
+        let predecessor = env::predecessor_account_id();
+        self.internal_claim(&predecessor, Some(amount.0)).into()
+    }
+
+    /// Claims every claimable token on behalf of each account.
+    ///
+    /// Accounts that are not registered are skipped.
+    ///
+    /// Returns how many raw SEAT tokens got claimed for each account, in the
+    /// same order.
+    ///
+    /// ###### Notes
+    ///
+    /// - Requires the Keeper role.
+    pub fn claim_for(
+        &mut self,
+        account_ids: Vec<AccountId>,
+    ) -> Vec<U128> {
+        self.assert_role(types::Role::Keeper);
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+
+        account_ids
+            .iter()
+            .map(|account_id| {
+                if self.accounts.contains_key(account_id) {
+                    self.internal_claim(account_id, None).into()
+                } else {
+                    U128(0)
+                }
+            })
+            .collect()
+    }
+
+    /// Claims every claimable token of the caller, and then transfers them
+    /// as in `ft_transfer_call`.
+    ///
+    /// ### Parameters
+    ///
+    /// - `receiver_id`: AccountId of the receiver.
+    /// - `msg`: Sent as the `msg` parameter on the `receiver_id`'s
+    /// `ft_on_transfer` method.
+    ///
+    /// ###### Notes
+    ///
+    /// - Requires exactly 1 yoctoNEAR to be attached.
+    /// - The tokens that the receiver doesn't use are refunded into the
+    /// caller's balance.
+    ///
+    /// ### Return
+    ///
+    /// Returns a stringfied 128-bit unsigned integer representation of the
+    /// amount of tokens that were used from the caller.
+    #[payable]
+    pub fn claim_and_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+        self.assert_not_paused(types::Feature::Transfers);
+        self.assert_non_minting();
+
+        near_sdk::assert_one_yocto();
         let predecessor = env::predecessor_account_id();
-        let mut user = self.internal_unwrap_user(&predecessor);
-        let amount = user.claim_balance;
-        user.claim_balance.0 = 0;
-        user.balance.0 += amount.0;
-        self.accounts.insert(&predecessor, &user);
+        let amount = self.internal_claim(&predecessor, None);
+        require!(amount > 0, "nothing to claim");
+
+        self.internal_transfer_call(predecessor, receiver_id, amount, None, msg)
+    }
+
+    /// Changes for how long the rewards of a user set can be claimed, after
+    /// which they are forfeited, or removes the limit if `null`.
+    ///
+    /// Returns the previous claim window.
+    ///
+    /// ###### Notes
+    ///
+    /// - Rewards that were already given can still be claimed for, at least,
+    /// the window they were given with. So a shorter window only applies to
+    /// the rewards given after this change, while a longer window, or no
+    /// window, also applies to the rewards already given.
+    /// - Forfeited tokens are burned or given to the treasury by the current
+    /// window.
+    pub fn change_vesting_set_claim_window(
+        &mut self,
+        name: types::SetName,
+        claim_window: Option<types::ClaimWindow>,
+    ) -> Option<types::ClaimWindow> {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let mut user_set = self.internal_unwrap_vesting_set(&name);
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        let previous = user_set.info.claim_window;
+        user_set.info.claim_window = claim_window;
+        self.mint.vesting.sets.insert(&name, &user_set);
+
+        previous
+    }
+
+    /// Changes before when the rewards of a user set can't be claimed, such
+    /// as until a listing date.
+    ///
+    /// Returns the previous unlock date.
+    ///
+    /// ###### Notes
+    ///
+    /// - Only rewards given after this change are affected, as the rewards
+    /// keep the unlock date that the set had when they were given.
+    pub fn change_vesting_set_claim_unlock(
+        &mut self,
+        name: types::SetName,
+        unlock_date: types::Timestamp,
+    ) -> types::Timestamp {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let mut user_set = self.internal_unwrap_vesting_set(&name);
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        let previous = user_set.info.claim_unlock_date;
+        user_set.info.claim_unlock_date = unlock_date;
+        self.mint.vesting.sets.insert(&name, &user_set);
+
+        previous
+    }
+
+    /// Forfeits the claimable tokens of each account whose claim window has
+    /// passed.
+    ///
+    /// Accounts that are not registered are skipped.
+    ///
+    /// Returns how many raw SEAT tokens got forfeited from each account, in
+    /// the same order.
+    ///
+    /// ###### Notes
+    ///
+    /// - Requires the Keeper role.
+    /// - Can't be called while the claims are paused, as the accounts can't
+    /// claim their tokens either.
+    /// - Expired tokens are also forfeited whenever the account claims.
+    pub fn forfeit_expired_claims(
+        &mut self,
+        account_ids: Vec<AccountId>,
+    ) -> Vec<U128> {
+        self.assert_role(types::Role::Keeper);
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+
+        account_ids
+            .iter()
+            .map(|account_id| match self.accounts.get(account_id) {
+                Some(mut user) => {
+                    let forfeited = self.internal_forfeit_expired_claims(account_id, &mut user);
+                    self.accounts.insert(account_id, &user);
+                    forfeited.into()
+                }
+                None => U128(0),
+            })
+            .collect()
+    }
+
+    /// Get the claimable tokens of an account, grouped by where they came
+    /// from.
+    ///
+    /// Tokens that are not in any bucket can be claimed at any time.
+    pub fn get_claim_buckets(
+        &self,
+        account_id: AccountId,
+    ) -> Vec<types::ClaimBucket> {
+        self.internal_unwrap_user(&account_id).claims
+    }
+
+    /// Get how many raw SEAT tokens an account can claim right now.
+    pub fn get_claimable(
+        &self,
+        account_id: AccountId,
+    ) -> U128 {
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.internal_unwrap_user(&account_id).claimable(now).into()
+    }
+}
 
-        if amount.0 > 0 {
+impl Seats {
+    /// Moves claimable tokens of the user into it's balance, or every
+    /// claimable token if without an amount.
+    ///
+    /// Returns how many raw SEAT tokens got claimed.
+    pub fn internal_claim(
+        &mut self,
+        account_id: &AccountId,
+        amount: Option<u128>,
+    ) -> u128 {
+        let now = types::Timestamp::from(env::block_timestamp());
+        let mut user = self.internal_unwrap_user(account_id);
+        self.internal_forfeit_expired_claims(account_id, &mut user);
+        let claimable = user.claimable(now);
+        let amount = amount.unwrap_or(claimable);
+        require!(
+            amount <= claimable,
+            &format!(
+                "cannot claim {} raw tokens, only {} are claimable",
+                amount, claimable
+            )
+        );
+
+        user.release_claims(amount, now);
+        self.internal_drop_unreserved_claims(account_id, &mut user);
+        user.claim_balance.0 -= amount;
+        // only unlocked tokens are released
+        self.supply
+            .remove_claim(types::Timestamp::default(), amount, now);
+        user.balance.0 += amount;
+        self.internal_on_balance_change(account_id, user.balance.0 - amount, user.balance.0);
+        self.accounts.insert(account_id, &user);
+
+        if amount > 0 {
             events::FtMint {
-                owner_id: &predecessor,
-                amount: &(amount.0 as u128).into(),
+                owner_id: account_id,
+                amount: &amount.into(),
                 memo: Some("claim"),
             }
             .emit();
@@ -25,4 +263,199 @@ impl Seats {
 
         amount
     }
+
+    /// Removes the user's buckets whose claim window has passed, giving their
+    /// tokens to the treasury or burning them.
+    ///
+    /// Each bucket has it's own expiry, so that only the oldest unclaimed
+    /// rewards of a member are forfeited.
+    ///
+    /// The user itself is not saved.
+    ///
+    /// Returns how many raw SEAT tokens got forfeited.
+    pub fn internal_forfeit_expired_claims(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut types::User,
+    ) -> u128 {
+        let now = types::Timestamp::from(env::block_timestamp());
+
+        let mut expired = vec![];
+        for bucket in user.claims.iter_mut().filter(|bucket| bucket.amount.0 > 0) {
+            let claim_window = match &bucket.source {
+                types::ClaimSource::Set { name, .. } => self
+                    .mint
+                    .vesting
+                    .sets
+                    .get(name)
+                    .and_then(|user_set| user_set.info.claim_window),
+                types::ClaimSource::ForceMint => None,
+            };
+            // expires by both the window it was rewarded with and the current
+            // one, so that shortening the window doesn't take earned rewards
+            match (bucket.expires_at, claim_window) {
+                (Some(expires_at), Some(claim_window))
+                    if now >= expires_at
+                        && now >= claim_window.expires_at(bucket.updated_at, bucket.unlock_at) =>
+                {
+                    expired.push((bucket.clone(), claim_window));
+                    bucket.amount.0 = 0;
+                }
+                _ => {}
+            }
+        }
+        self.internal_drop_unreserved_claims(account_id, user);
+
+        let mut forfeited = 0;
+        for (bucket, claim_window) in expired {
+            let amount = bucket.amount.0;
+            user.claim_balance.0 -= amount;
+            self.supply.remove_claim(bucket.unlock_at, amount, now);
+            forfeited += amount;
+
+            let treasury_id = match &self.treasury {
+                Some(treasury_id)
+                    if !claim_window.burn && self.accounts.contains_key(treasury_id) =>
+                {
+                    Some(treasury_id.clone())
+                }
+                _ => None,
+            };
+            match &treasury_id {
+                Some(treasury_id) if treasury_id == account_id => {
+                    user.balance.0 += amount;
+                    self.internal_on_balance_change(
+                        account_id,
+                        user.balance.0 - amount,
+                        user.balance.0,
+                    );
+                }
+                Some(treasury_id) => {
+                    // the tokens were already in the total supply
+                    self.total_supply -= amount;
+                    let treasury = self.internal_unwrap_user(treasury_id);
+                    self.internal_user_deposit(treasury_id, treasury, amount, None);
+                }
+                None => {
+                    self.total_supply -= amount;
+                    self.internal_checkpoint_supply();
+                }
+            }
+
+            seat_events::ClaimForfeit {
+                account_id,
+                source: &bucket.source,
+                amount: amount.into(),
+                treasury_id: treasury_id.as_ref(),
+            }
+            .emit();
+            if let Some(treasury_id) = &treasury_id {
+                events::FtMint {
+                    owner_id: treasury_id,
+                    amount: &amount.into(),
+                    memo: Some("forfeit"),
+                }
+                .emit();
+            }
+        }
+
+        forfeited
+    }
+    /// Adds an empty bucket, into the account, for the tokens from the set
+    /// that are earned by the member, so that it's storage is paid by the
+    /// caller instead of by the minting.
+    ///
+    /// Accounts that are not registered are skipped.
+    pub fn internal_reserve_claim_bucket(
+        &mut self,
+        set: &types::SetName,
+        member_id: &AccountId,
+        account_id: &AccountId,
+    ) {
+        if let Some(mut user) = self.accounts.get(account_id) {
+            let source = types::ClaimSource::Set {
+                name: set.clone(),
+                member: member_id.clone(),
+            };
+            if user.reserve_claim(source) {
+                self.accounts.insert(account_id, &user);
+            }
+        }
+    }
+
+    /// Removes the account's bucket for the tokens from the set that were
+    /// earned by the member, if it's empty.
+    ///
+    /// Accounts that are not registered are skipped.
+    pub fn internal_release_claim_bucket(
+        &mut self,
+        set: &types::SetName,
+        member_id: &AccountId,
+        account_id: &AccountId,
+    ) {
+        if let Some(mut user) = self.accounts.get(account_id) {
+            let count = user.claims.len();
+            user.claims
+                .retain(|bucket| !bucket.is_from(set, member_id) || bucket.amount.0 > 0);
+            if user.claims.len() != count {
+                self.accounts.insert(account_id, &user);
+            }
+        }
+    }
+
+    /// Removes the user's empty buckets, except for one bucket of each set
+    /// membership or receiver that is still reserved.
+    ///
+    /// The user itself is not saved.
+    fn internal_drop_unreserved_claims(
+        &self,
+        account_id: &AccountId,
+        user: &mut types::User,
+    ) {
+        let claims = std::mem::take(&mut user.claims);
+        for bucket in &claims {
+            let kept = bucket.amount.0 > 0 || {
+                // a single empty bucket is kept, if it's source has no tokens
+                let has_tokens = claims
+                    .iter()
+                    .any(|other| other.source == bucket.source && other.amount.0 > 0);
+                let has_bucket = user
+                    .claims
+                    .iter()
+                    .any(|other| other.source == bucket.source);
+                !has_tokens
+                    && !has_bucket
+                    && self.internal_is_claim_reserved(account_id, &bucket.source)
+            };
+            if kept {
+                user.claims.push(bucket.clone());
+            }
+        }
+    }
+
+    /// Whether the account's bucket for the source is reserved for a set
+    /// membership or receiver.
+    fn internal_is_claim_reserved(
+        &self,
+        account_id: &AccountId,
+        source: &types::ClaimSource,
+    ) -> bool {
+        match source {
+            types::ClaimSource::Set { name, member } if member == account_id => self
+                .mint
+                .vesting
+                .sets
+                .get(name)
+                .map(|user_set| user_set.accounts.get(member).is_some())
+                .unwrap_or_default(),
+            types::ClaimSource::Set { name, member } => self
+                .mint
+                .vesting
+                .receivers
+                .get(&(name.clone(), member.clone()))
+                .map(|receiver_id| &receiver_id == account_id)
+                .unwrap_or_default(),
+            types::ClaimSource::ForceMint => false,
+        }
+    }
 }
@@ -0,0 +1,166 @@
+//! Delegation of voting power, where the votes of each delegate follow the
+//! balances of it's delegators.
+//!
+//! Holders don't have any votes until they delegate, even if to themselves.
+//!
+//! As with the balances, only the last votes of each mint generation are
+//! kept in a delegate's history, which is paid by the delegate's prepaid
+//! history.
+
+use crate::{
+    checkpoints::{push_checkpoint, value_at},
+    events, types, Seats, StorageKey,
+};
+use near_sdk::{collections::Vector, env, json_types::U128, near_bindgen, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Delegates the caller's votes to an account, or stops delegating them
+    /// if `null`.
+    ///
+    /// To vote with it's own tokens, the caller must delegate to itself.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// delegation, and any extra deposit is refunded.
+    #[payable]
+    pub fn delegate(
+        &mut self,
+        to: Option<AccountId>,
+    ) {
+        self.assert_non_migration();
+
+        let delegator_id = env::predecessor_account_id();
+        self.internal_assert_registered(&delegator_id);
+
+        common::refund_deposit(|| self.internal_delegate(&delegator_id, to.clone()));
+    }
+
+    /// Get to whom an account delegates it's votes.
+    pub fn get_delegate(
+        &self,
+        account_id: AccountId,
+    ) -> Option<AccountId> {
+        self.delegation.delegates.get(&account_id)
+    }
+
+    /// Get the votes currently delegated to an account.
+    pub fn get_votes(
+        &self,
+        account_id: AccountId,
+    ) -> U128 {
+        self.delegation
+            .votes
+            .get(&account_id)
+            .and_then(|votes| votes.get(votes.len().checked_sub(1)?))
+            .map(|checkpoint| checkpoint.value)
+            .unwrap_or_else(|| 0.into())
+    }
+
+    /// Get the votes that were delegated to an account at a past point.
+    ///
+    /// ### Parameters
+    ///
+    /// - `account_id`: The delegate.
+    /// - `timestamp_or_generation`: Either a timestamp or a mint generation,
+    /// such as `{"t": "Generation", "c": 3}`.
+    ///
+    /// ###### Notes
+    ///
+    /// - Since only the last votes of each mint generation are kept, a
+    /// timestamp between two changes of the same generation gets the votes
+    /// from the end of the previous generation.
+    /// - Once the delegate's prepaid history is used up, or if it isn't
+    /// registered, the generations whose checkpoints got replaced get older
+    /// votes.
+    pub fn get_past_votes(
+        &self,
+        account_id: AccountId,
+        timestamp_or_generation: types::PastPoint,
+    ) -> U128 {
+        self.delegation
+            .votes
+            .get(&account_id)
+            .map(|votes| value_at(&votes, timestamp_or_generation))
+            .unwrap_or_default()
+            .into()
+    }
+}
+
+impl Seats {
+    /// Changes to whom an account delegates it's votes, moving the votes of
+    /// it's current balance.
+    pub fn internal_delegate(
+        &mut self,
+        delegator_id: &AccountId,
+        to: Option<AccountId>,
+    ) {
+        let previous = match &to {
+            Some(to) => self.delegation.delegates.insert(delegator_id, to),
+            None => self.delegation.delegates.remove(delegator_id),
+        };
+        if previous == to {
+            return;
+        }
+
+        let balance = self
+            .accounts
+            .get(delegator_id)
+            .map(|user| user.balance.0)
+            .unwrap_or_default();
+        if let Some(previous) = &previous {
+            self.internal_move_votes(previous, balance, 0);
+        }
+        if let Some(to) = &to {
+            self.internal_move_votes(to, 0, balance);
+        }
+
+        events::DelegateChange {
+            delegator_id,
+            from_delegate_id: previous.as_ref(),
+            to_delegate_id: to.as_ref(),
+        }
+        .emit();
+    }
+
+    /// Changes the votes of a delegate by the change of a delegator's
+    /// balance.
+    pub fn internal_move_votes(
+        &mut self,
+        delegate_id: &AccountId,
+        previous_balance: Balance,
+        balance: Balance,
+    ) {
+        if previous_balance == balance {
+            return;
+        }
+
+        let mut votes = self.delegation.votes.get(delegate_id).unwrap_or_else(|| {
+            Vector::new(StorageKey::AccountVotes {
+                account_id: delegate_id.clone(),
+            })
+        });
+        let previous_votes = votes
+            .len()
+            .checked_sub(1)
+            .and_then(|last_index| votes.get(last_index))
+            .map(|checkpoint| checkpoint.value.0)
+            .unwrap_or_default();
+        let new_votes = previous_votes + balance - previous_balance;
+
+        let generation = self.internal_checkpoint_generation();
+        self.internal_charge_history(delegate_id, |this, can_grow| {
+            push_checkpoint(&mut votes, generation, new_votes, can_grow);
+            this.delegation.votes.insert(delegate_id, &votes);
+        });
+
+        events::DelegateVotesChange {
+            delegate_id,
+            previous_votes: previous_votes.into(),
+            new_votes: new_votes.into(),
+        }
+        .emit();
+    }
+}
@@ -9,6 +9,7 @@ use near_sdk::{env, serde::Serialize};
 #[serde(rename_all = "snake_case")]
 pub(crate) enum NearEvent<'a> {
     Nep141(crate::fungible_token::events::Nep141Event<'a>),
+    Seat(crate::events::SeatEvent<'a>),
 }
 
 impl<'a> NearEvent<'a> {
@@ -0,0 +1,349 @@
+//! Events specific to the SEAT contract.
+//!
+//! These follow the events format (nep-297), under the `seat` standard:
+//! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
+//!
+//! These events can be logged by calling `.emit()` on them.
+
+use crate::{event::NearEvent, types};
+use near_sdk::{
+    json_types::{U128, U64},
+    serde::Serialize,
+    AccountId,
+};
+
+/// Data to log when features get paused. To log this event, call
+/// [`.emit()`](Pause::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Pause<'a> {
+    pub account_id: &'a AccountId,
+    pub features: &'a [types::Feature],
+}
+
+impl Pause<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::Pause(&[self])).emit()
+    }
+}
+
+/// Data to log when features get unpaused. To log this event, call
+/// [`.emit()`](Unpause::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Unpause<'a> {
+    pub account_id: &'a AccountId,
+    pub features: &'a [types::Feature],
+}
+
+impl Unpause<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::Unpause(&[self])).emit()
+    }
+}
+
+/// Data to log when the token metadata gets updated. To log this event, call
+/// [`.emit()`](MetadataUpdate::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataUpdate<'a> {
+    pub account_id: &'a AccountId,
+    /// How many times the metadata got updated, including this update.
+    pub version: u64,
+}
+
+impl MetadataUpdate<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::MetadataUpdate(&[self])).emit()
+    }
+}
+
+/// Data to log when a member gets moved from a vesting set into another. To
+/// log this event, call [`.emit()`](VestingUserMove::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingUserMove<'a> {
+    pub account_id: &'a AccountId,
+    pub from_set: &'a types::SetName,
+    pub to_set: &'a types::SetName,
+    pub previous_percentage: types::RewardPercentage,
+    pub new_percentage: types::RewardPercentage,
+}
+
+impl VestingUserMove<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::VestingUserMove(&[self])).emit()
+    }
+}
+
+/// Data to log when a member gets revoked from a vesting set. To log this
+/// event, call [`.emit()`](VestingUserRevoke::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingUserRevoke<'a> {
+    pub account_id: &'a AccountId,
+    pub set: &'a types::SetName,
+    pub treasury_id: &'a AccountId,
+    pub percentage: types::RewardPercentage,
+    pub clawed_back: U128,
+}
+
+impl VestingUserRevoke<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::VestingUserRevoke(&[self])).emit()
+    }
+}
+
+/// Data to log when claimable tokens get forfeited, after their claim window
+/// has passed. To log this event, call [`.emit()`](ClaimForfeit::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimForfeit<'a> {
+    pub account_id: &'a AccountId,
+    pub source: &'a types::ClaimSource,
+    pub amount: U128,
+    /// The account that received the tokens, or `None` if they were burned.
+    pub treasury_id: Option<&'a AccountId>,
+}
+
+impl ClaimForfeit<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::ClaimForfeit(&[self])).emit()
+    }
+}
+
+/// Data to log when an account changes to whom it delegates it's votes. To
+/// log this event, call [`.emit()`](DelegateChange::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegateChange<'a> {
+    pub delegator_id: &'a AccountId,
+    pub from_delegate_id: Option<&'a AccountId>,
+    pub to_delegate_id: Option<&'a AccountId>,
+}
+
+impl DelegateChange<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::DelegateChange(&[self])).emit()
+    }
+}
+
+/// Data to log when the votes delegated to an account change. To log this
+/// event, call [`.emit()`](DelegateVotesChange::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegateVotesChange<'a> {
+    pub delegate_id: &'a AccountId,
+    pub previous_votes: U128,
+    pub new_votes: U128,
+}
+
+impl DelegateVotesChange<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::DelegateVotesChange(&[self])).emit()
+    }
+}
+
+/// Data to log when a governance proposal gets created. To log this event,
+/// call [`.emit()`](ProposalCreate::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalCreate<'a> {
+    pub proposal_id: U64,
+    pub proposer_id: &'a AccountId,
+    pub voting_ends_at: types::Timestamp,
+}
+
+impl ProposalCreate<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::ProposalCreate(&[self])).emit()
+    }
+}
+
+/// Data to log when an account votes on a governance proposal. To log this
+/// event, call [`.emit()`](ProposalVote::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalVote<'a> {
+    pub proposal_id: U64,
+    pub account_id: &'a AccountId,
+    pub vote: &'a types::Vote,
+}
+
+impl ProposalVote<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::ProposalVote(&[self])).emit()
+    }
+}
+
+/// Data to log when a governance proposal gets executed. To log this event,
+/// call [`.emit()`](ProposalExecute::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalExecute<'a> {
+    pub proposal_id: U64,
+    pub account_id: &'a AccountId,
+}
+
+impl ProposalExecute<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::ProposalExecute(&[self])).emit()
+    }
+}
+
+/// Data to log when a token stream gets created. To log this event, call
+/// [`.emit()`](StreamCreate::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamCreate<'a> {
+    pub stream_id: U64,
+    pub stream: &'a types::Stream,
+}
+
+impl StreamCreate<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::StreamCreate(&[self])).emit()
+    }
+}
+
+/// Data to log when the receiver of a token stream withdraws from it. To log
+/// this event, call [`.emit()`](StreamWithdraw::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamWithdraw<'a> {
+    pub stream_id: U64,
+    pub receiver_id: &'a AccountId,
+    pub amount: U128,
+}
+
+impl StreamWithdraw<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::StreamWithdraw(&[self])).emit()
+    }
+}
+
+/// Data to log when a token stream gets canceled, and it's tokens split
+/// between the sender and the receiver. To log this event, call
+/// [`.emit()`](StreamCancel::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamCancel<'a> {
+    pub stream_id: U64,
+    pub account_id: &'a AccountId,
+    pub sender_amount: U128,
+    pub receiver_amount: U128,
+}
+
+impl StreamCancel<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_seat_v1(SeatEventKind::StreamCancel(&[self])).emit()
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct SeatEvent<'a> {
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: SeatEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+enum SeatEventKind<'a> {
+    Pause(&'a [Pause<'a>]),
+    Unpause(&'a [Unpause<'a>]),
+    MetadataUpdate(&'a [MetadataUpdate<'a>]),
+    VestingUserMove(&'a [VestingUserMove<'a>]),
+    VestingUserRevoke(&'a [VestingUserRevoke<'a>]),
+    ClaimForfeit(&'a [ClaimForfeit<'a>]),
+    DelegateChange(&'a [DelegateChange<'a>]),
+    DelegateVotesChange(&'a [DelegateVotesChange<'a>]),
+    ProposalCreate(&'a [ProposalCreate<'a>]),
+    ProposalVote(&'a [ProposalVote<'a>]),
+    ProposalExecute(&'a [ProposalExecute<'a>]),
+    StreamCreate(&'a [StreamCreate<'a>]),
+    StreamWithdraw(&'a [StreamWithdraw<'a>]),
+    StreamCancel(&'a [StreamCancel<'a>]),
+}
+
+fn new_seat<'a>(
+    version: &'static str,
+    event_kind: SeatEventKind<'a>,
+) -> NearEvent<'a> {
+    NearEvent::Seat(SeatEvent {
+        version,
+        event_kind,
+    })
+}
+
+fn new_seat_v1(event_kind: SeatEventKind) -> NearEvent {
+    new_seat("1.0.0", event_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils, AccountId};
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    #[test]
+    fn pause() {
+        Pause {
+            account_id: &bob(),
+            features: &[types::Feature::Transfers, types::Feature::Claims],
+        }
+        .emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"seat","version":"1.0.0","event":"pause","data":[{"account_id":"bob","features":["Transfers","Claims"]}]}"#
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Seats;
+use crate::{types, Seats};
 use near_contract_standards::fungible_token::{
     core::FungibleTokenCore,
     core_impl::{ext_fungible_token_receiver, ext_self},
@@ -36,6 +36,7 @@ impl FungibleTokenCore for Seats {
         memo: Option<String>,
     ) {
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
         self.assert_non_minting();
 
         near_sdk::assert_one_yocto();
@@ -82,34 +83,13 @@ impl FungibleTokenCore for Seats {
         msg: String,
     ) -> PromiseOrValue<U128> {
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
         self.assert_non_minting();
 
         near_sdk::assert_one_yocto();
-        require!(
-            env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER,
-            "More gas is required"
-        );
         let sender_id = env::predecessor_account_id();
         let amount: Balance = amount.into();
-        self.internal_transfer(&sender_id, &receiver_id, amount, memo);
-        // Initiating receiver's call and the callback
-        ext_fungible_token_receiver::ft_on_transfer(
-            sender_id.clone(),
-            amount.into(),
-            msg,
-            receiver_id.clone(),
-            NO_DEPOSIT,
-            env::prepaid_gas() - GAS_FOR_FT_TRANSFER_CALL,
-        )
-        .then(ext_self::ft_resolve_transfer(
-            sender_id,
-            receiver_id,
-            amount.into(),
-            env::current_account_id(),
-            NO_DEPOSIT,
-            GAS_FOR_RESOLVE_TRANSFER,
-        ))
-        .into()
+        self.internal_transfer_call(sender_id, receiver_id, amount, memo, msg)
     }
 
     /// Gets the total supply of raw SEAT tokens.
@@ -141,3 +121,43 @@ impl FungibleTokenCore for Seats {
         self.accounts.get(&account_id).unwrap_or_default().balance
     }
 }
+
+impl Seats {
+    /// Transfers the tokens and then calls the `ft_on_transfer` method on the
+    /// receiver, as in `ft_transfer_call`.
+    ///
+    /// The deposit and other requirements must have been checked by the
+    /// caller.
+    pub fn internal_transfer_call(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: Balance,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER,
+            "More gas is required"
+        );
+        self.internal_transfer(&sender_id, &receiver_id, amount, memo);
+        // Initiating receiver's call and the callback
+        ext_fungible_token_receiver::ft_on_transfer(
+            sender_id.clone(),
+            amount.into(),
+            msg,
+            receiver_id.clone(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_FT_TRANSFER_CALL,
+        )
+        .then(ext_self::ft_resolve_transfer(
+            sender_id,
+            receiver_id,
+            amount.into(),
+            env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
+    }
+}
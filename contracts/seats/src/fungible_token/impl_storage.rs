@@ -22,6 +22,13 @@ impl StorageManagement for Seats {
     /// this contract, in which case any extra paid deposit is returned back to
     /// the caller.
     ///
+    /// ###### Notes
+    ///
+    /// - The attached deposit must be at least the minimum storage balance,
+    /// but only the storage actually used by the account is charged, plus the
+    /// [`HISTORY`](crate::storage_costs::user::HISTORY) prepaid for the growth
+    /// of it's balance and vote history.
+    ///
     /// ### Return
     ///
     /// Returns the [`StorageBalance`] showing updated balances.
@@ -34,6 +41,7 @@ impl StorageManagement for Seats {
         let _registration_only = registration_only;
 
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Registrations);
         self.assert_non_minting();
 
         let amount: Balance = env::attached_deposit();
@@ -50,9 +58,26 @@ impl StorageManagement for Seats {
                 "The attached deposit is less than the minimum storage balance"
             );
 
+            // only the storage actually used and the prepaid history are
+            // charged, and they are tracked so that they can be refunded on
+            // unregistration
+            let history = crate::storage_costs::user::HISTORY;
+            let initial_storage_usage = env::storage_usage();
             self.internal_register_account(&account_id);
-            let refund = amount - min_balance;
-            if refund > 0 {
+            self.checkpoints.allowances.insert(&account_id, &history);
+            let used_storage = env::storage_usage() - initial_storage_usage;
+            let charged = env::storage_byte_cost() * Balance::from(used_storage) + history;
+            require!(
+                amount >= charged,
+                &format!("Must attach {} yoctoNEAR to cover storage", charged)
+            );
+
+            let mut user = self.internal_unwrap_user(&account_id);
+            user.storage_deposit = charged.into();
+            self.accounts.insert(&account_id, &user);
+
+            let refund = amount - charged;
+            if refund > 1 {
                 Promise::new(env::predecessor_account_id()).transfer(refund);
             }
         }
@@ -119,6 +144,10 @@ impl StorageManagement for Seats {
     ///
     /// ###### Notes
     ///
+    /// - The freed storage and the unused prepaid history are refunded, up to
+    /// the deposit paid by `storage_deposit` for the account's registration.
+    /// The storage of the account's claims and of it's recorded history is
+    /// not.
     /// - If the user being removed is still registered as a vesting user,
     /// then that user won't get any token rewards during vesting user minting
     /// operations - ie. that user is skipped during rewards, and the tokens it
@@ -134,10 +163,27 @@ impl StorageManagement for Seats {
         force: Option<bool>,
     ) -> bool {
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Registrations);
         self.assert_non_minting();
 
+        let initial_storage_usage = env::storage_usage();
         #[allow(unused_variables)]
         if let Some((account_id, user)) = self.internal_storage_unregister(force) {
+            // the freed storage and the unused prepaid history are refunded up
+            // to what the account deposited
+            let history = self
+                .checkpoints
+                .allowances
+                .remove(&account_id)
+                .unwrap_or_default();
+            let freed_storage = initial_storage_usage.saturating_sub(env::storage_usage());
+            let freed_cost = env::storage_byte_cost() * Balance::from(freed_storage);
+            let refund = std::cmp::min(freed_cost + history, user.storage_deposit.0)
+                + env::attached_deposit();
+            if refund > 1 {
+                Promise::new(account_id.clone()).transfer(refund);
+            }
+
             self.on_account_closed(account_id, user.balance.0);
             true
         } else {
@@ -174,6 +220,9 @@ impl StorageManagement for Seats {
     ///
     /// Optionally returns the [`StorageBalance`] information of the given
     /// user. Returns `null` if the account is not registered.
+    ///
+    /// The `total` is the deposit that was charged for the account's
+    /// registration, which is zero if the owner registered it.
     fn storage_balance_of(
         &self,
         account_id: AccountId,
@@ -213,21 +262,33 @@ impl Seats {
                     (user.balance.0 == 0 && user.claim_balance.0 == 0) || force,
                     "Can't unregister the account with the positive token (or claimable token) balance without force"
                 );
+                require!(
+                    !self.staking.stakes.contains_key(&account_id) || force,
+                    "Can't unregister the account with a stake without force"
+                );
 
                 self.accounts.remove(&account_id);
+                self.account_ids.remove(&account_id);
+                self.internal_on_balance_change(&account_id, user.balance.0, 0);
+                self.internal_delegate(&account_id, None);
                 self.total_supply -= user.balance.0;
                 self.total_supply -= user.claim_balance.0;
+                let staked = self.internal_remove_stake(&account_id);
+                self.total_supply -= staked;
+                self.internal_checkpoint_supply();
+                self.supply
+                    .remove_user(&user, types::Timestamp::from(env::block_timestamp()));
 
-                if user.balance.0 > 0 {
+                let burned = user.balance.0 + staked;
+                if burned > 0 {
                     events::FtBurn {
                         owner_id: &account_id,
-                        amount: &user.balance,
+                        amount: &burned.into(),
                         memo: Some("unregister"),
                     }
                     .emit();
                 }
 
-                Promise::new(account_id.clone()).transfer(self.storage_balance_bounds().min.0 + 1);
                 Some((account_id, user))
             }
         }
@@ -237,13 +298,9 @@ impl Seats {
         &self,
         account_id: &AccountId,
     ) -> Option<StorageBalance> {
-        if self.accounts.contains_key(account_id) {
-            Some(StorageBalance {
-                total: self.storage_balance_bounds().min,
-                available: 0.into(),
-            })
-        } else {
-            None
-        }
+        self.accounts.get(account_id).map(|user| StorageBalance {
+            total: user.storage_deposit,
+            available: 0.into(),
+        })
     }
 }
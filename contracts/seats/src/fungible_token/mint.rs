@@ -23,10 +23,7 @@ impl Seats {
         new_start_timestamp_seconds: u32,
     ) {
         self.assert_owner();
-        self.assert_non_migration();
-        self.assert_non_minting();
-
-        self.start_timestamp = types::Timestamp::from_seconds(new_start_timestamp_seconds);
+        self.internal_change_start_timestamp(new_start_timestamp_seconds);
     }
 
     /// Get the start_timestamp in which the minting will start being available.
@@ -45,6 +42,11 @@ impl Seats {
     /// around 180 trillion SEAT tokens.
     /// - `must_claim`: Whether the user must claim the minted tokens, or if he
     /// will immediatedly receive the tokens.
+    ///
+    /// ###### Notes
+    ///
+    /// - The amount is limited by both the global and the caller's mint
+    /// budgets. See [`Self::change_mint_budget()`].
     pub fn force_mint(
         &mut self,
         account_id: AccountId,
@@ -53,9 +55,20 @@ impl Seats {
     ) {
         self.assert_owner();
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Minting);
+        self.internal_consume_mint_budget(&env::predecessor_account_id(), amount.0 as u128);
         let user = self.internal_unwrap_user(&account_id);
         let must_claim = must_claim.unwrap_or_default();
-        self.internal_user_deposit(&account_id, user, amount.0 as u128, must_claim);
+        let claim = if must_claim {
+            Some(types::ClaimTerms {
+                source: types::ClaimSource::ForceMint,
+                unlock_at: types::Timestamp::default(),
+                expires_at: None,
+            })
+        } else {
+            None
+        };
+        self.internal_user_deposit(&account_id, user, amount.0 as u128, claim);
 
         if !must_claim {
             events::FtMint {
@@ -67,6 +80,122 @@ impl Seats {
         }
     }
 
+    /// Changes the limits for the `force_mint` operations.
+    ///
+    /// Changes that don't raise the limits apply right away, and cancel any
+    /// pending change. Changes that raise them only apply after
+    /// [`types::MintBudget::RAISE_DELAY_SECONDS`], and can be canceled by any
+    /// owner in the meantime.
+    ///
+    /// Returns the pending change, if the limits got raised.
+    ///
+    /// ### Parameters
+    ///
+    /// - `window_seconds`: For how many seconds a budget window lasts, after
+    /// which the budget usage gets reset.
+    /// - `global_limit`: How many raw SEAT tokens all owners together can
+    /// force-mint within a window. If `null`, there is no global limit.
+    /// - `owner_limit`: How many raw SEAT tokens each owner can force-mint
+    /// within a window. If `null`, there is no per-owner limit.
+    ///
+    /// ###### Notes
+    ///
+    /// - The usage from the current windows are kept.
+    /// - A shorter window counts as raising the limits.
+    pub fn change_mint_budget(
+        &mut self,
+        window_seconds: u32,
+        global_limit: Option<types::RewardAmount>,
+        owner_limit: Option<types::RewardAmount>,
+    ) -> Option<types::PendingMintBudget> {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.mint.budget.apply_pending(now);
+
+        let config = types::MintBudgetConfig {
+            window_seconds,
+            global_limit,
+            owner_limit,
+        };
+        self.mint.budget.pending = if config.is_within(&self.mint.budget.config()) {
+            self.mint.budget.set_config(config);
+            None
+        } else {
+            Some(types::PendingMintBudget {
+                config,
+                applies_at: now
+                    + types::Timestamp::from_seconds(types::MintBudget::RAISE_DELAY_SECONDS),
+            })
+        };
+        self.mint.budget.pending
+    }
+
+    /// Cancels a pending change that raises the `force_mint` limits.
+    ///
+    /// Returns the canceled change.
+    pub fn cancel_mint_budget_change(&mut self) -> Option<types::PendingMintBudget> {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.mint.budget.apply_pending(now);
+        self.mint.budget.pending.take()
+    }
+
+    /// Get the limits for the `force_mint` operations.
+    pub fn get_mint_budget(&self) -> types::MintBudgetConfig {
+        let now = types::Timestamp::from(env::block_timestamp());
+        match self.mint.budget.pending {
+            Some(pending) if now >= pending.applies_at => pending.config,
+            _ => self.mint.budget.config(),
+        }
+    }
+
+    /// Get the change that raises the `force_mint` limits and is waiting for
+    /// it's timelock, if any.
+    pub fn get_pending_mint_budget(&self) -> Option<types::PendingMintBudget> {
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.mint
+            .budget
+            .pending
+            .filter(|pending| now < pending.applies_at)
+    }
+
+    /// Get the remaining global budget for `force_mint` operations, and when
+    /// it will get reset.
+    pub fn get_global_mint_budget(&self) -> types::MintBudgetStatus {
+        let now = types::Timestamp::from(env::block_timestamp());
+        let config = self.get_mint_budget();
+        let window = types::Timestamp::from_seconds(config.window_seconds);
+        self.mint
+            .budget
+            .global_usage
+            .status(config.global_limit, now, window)
+    }
+
+    /// Get the remaining budget of an owner for `force_mint` operations, and
+    /// when it will get reset.
+    ///
+    /// ###### Notes
+    ///
+    /// - The global budget may still be lower than the owner's budget.
+    pub fn get_owner_mint_budget(
+        &self,
+        owner_id: AccountId,
+    ) -> types::MintBudgetStatus {
+        let now = types::Timestamp::from(env::block_timestamp());
+        let config = self.get_mint_budget();
+        let window = types::Timestamp::from_seconds(config.window_seconds);
+        self.mint
+            .budget
+            .owner_usage
+            .get(&owner_id)
+            .unwrap_or_default()
+            .status(config.owner_limit, now, window)
+    }
+
     /// Starts the minting procedure.
     ///
     /// Maximum reward value is 18446744073709551615 (~18 mN, in yN units),
@@ -76,6 +205,7 @@ impl Seats {
 
         self.assert_owner();
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Minting);
 
         let now = types::Timestamp::from(near_sdk::env::block_timestamp());
         require!(
@@ -132,6 +262,7 @@ impl Seats {
         use types::MintState;
 
         self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Minting);
 
         let limit = limit.unwrap_or(100);
 
@@ -165,16 +296,37 @@ impl Seats {
                     // or if it's not enabled
                     let user_len = set_value.accounts.len();
                     let last_mint_timestamp = self.mint.last_mint_timestamp.unwrap_or_default();
-                    if
-                    // no more members on the set
-                    user_offset >= user_len
+                    let inactive =
                     // user set not yet started
-                    || last_mint_timestamp < set_value.info.start_date
+                    last_mint_timestamp < set_value.info.start_date
                     // user set expired 
                     || last_mint_timestamp >= set_value.info.expiration_date
-                    {
+                    // user set being removed
+                    || set_value.info.removing;
+
+                    // the staking set rewards the stakers instead of it's members
+                    if !inactive && self.staking.config.reward_set.as_ref() == Some(&set_key) {
+                        let distributed =
+                            self.internal_distribute_staking_reward(set_value.info.reward.0);
+                        set_value.info.total_rewarded.0 += distributed;
+
+                        // last update to set information
+                        set_value.info.generation += 1;
+                        set_value.info.last_mint_timestamp = self.mint.last_mint_timestamp.unwrap();
+                        self.mint.vesting.sets.insert(&set_key, &set_value);
+
+                        // moves to the next user set
+                        set_offset += 1;
+                        user_offset = 0;
+                        i += 1;
+                        continue 'outer;
+                    }
+
+                    if
+                    // no more members on the set
+                    user_offset >= user_len || inactive {
                         // for when the set was active
-                        if user_offset >= user_len {
+                        if user_offset >= user_len && !set_value.info.removing {
                             // last update to set information
                             set_value.info.generation += 1;
                             set_value.info.last_mint_timestamp =
@@ -218,10 +370,25 @@ impl Seats {
                         };
 
                         let reward = percentage.to_reward(set_value.info.reward);
+                        let member_id = account_id;
+                        let account_id =
+                            self.internal_vesting_beneficiary(&set_key, member_id.clone());
 
                         // this takes self by ref mut
-                        let deposited =
-                            self.try_internal_deposit(&account_id, reward, users_must_claim);
+                        let now = types::Timestamp::from(env::block_timestamp());
+                        let unlock_at = set_value.info.claim_unlock_date;
+                        let claim = users_must_claim.then(|| types::ClaimTerms {
+                            source: types::ClaimSource::Set {
+                                name: set_key.clone(),
+                                member: member_id,
+                            },
+                            unlock_at,
+                            expires_at: set_value
+                                .info
+                                .claim_window
+                                .map(|claim_window| claim_window.expires_at(now, unlock_at)),
+                        });
+                        let deposited = self.try_internal_deposit(&account_id, reward, claim);
                         // note: in case the deposit has failed
                         // (user unregistered, then the tokens are effectivelly
                         //  burned, ie. not created)
@@ -279,6 +446,43 @@ impl Seats {
 }
 
 impl Seats {
+    /// Uses `amount` from both the global and the `owner_id` mint budgets.
+    pub fn internal_consume_mint_budget(
+        &mut self,
+        owner_id: &AccountId,
+        amount: u128,
+    ) {
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.mint.budget.apply_pending(now);
+        let window = types::Timestamp::from_seconds(self.mint.budget.window_seconds);
+
+        let global_limit = self.mint.budget.global_limit;
+        self.mint
+            .budget
+            .global_usage
+            .consume(amount, global_limit, now, window, "global");
+
+        let mut owner_usage = self
+            .mint
+            .budget
+            .owner_usage
+            .get(owner_id)
+            .unwrap_or_default();
+        owner_usage.consume(amount, self.mint.budget.owner_limit, now, window, "owner");
+        self.mint.budget.owner_usage.insert(owner_id, &owner_usage);
+    }
+
+    /// Changes the start_timestamp, without checking the caller.
+    pub fn internal_change_start_timestamp(
+        &mut self,
+        new_start_timestamp_seconds: u32,
+    ) {
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        self.start_timestamp = types::Timestamp::from_seconds(new_start_timestamp_seconds);
+    }
+
     pub fn internal_end_minting(&mut self) -> types::MintState {
         self.mint.generation += 1;
         self.mint.state = types::MintState::Standby;
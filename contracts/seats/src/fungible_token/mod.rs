@@ -47,38 +47,44 @@ impl Seats {
         &mut self,
         account_id: &AccountId,
         amount: Balance,
-        must_claim: bool,
+        claim: Option<types::ClaimTerms>,
     ) -> bool {
         match self.accounts.get(account_id) {
             Some(user) => {
-                self.internal_user_deposit(account_id, user, amount, must_claim);
+                self.internal_user_deposit(account_id, user, amount, claim);
                 true
             }
             None => false,
         }
     }
 
+    /// Deposits some amount to the user.
+    ///
+    /// If with claim terms, the amount must still be claimed by the user.
     pub fn internal_user_deposit(
         &mut self,
         account_id: &AccountId,
         mut user: types::User,
         amount: Balance,
-        must_claim: bool,
+        claim: Option<types::ClaimTerms>,
     ) {
-        if must_claim {
+        if let Some(claim) = claim {
+            let now = types::Timestamp::from(env::block_timestamp());
             user.claim_balance = user
                 .claim_balance
                 .0
                 .checked_add(amount)
                 .unwrap_or_else(|| env::panic_str("Claim balance overflow"))
                 .into();
+            self.supply.add_claim(claim.unlock_at, amount, now);
+            user.add_claim(claim, now, amount);
         } else {
-            user.balance = user
-                .balance
-                .0
+            let balance = user.balance.0;
+            user.balance = balance
                 .checked_add(amount)
                 .unwrap_or_else(|| env::panic_str("Balance overflow"))
                 .into();
+            self.internal_on_balance_change(account_id, balance, user.balance.0);
         };
 
         self.accounts.insert(account_id, &user);
@@ -86,6 +92,7 @@ impl Seats {
             .total_supply
             .checked_add(amount)
             .unwrap_or_else(|| env::panic_str("Total supply overflow"));
+        self.internal_checkpoint_supply();
     }
 
     pub fn internal_withdraw(
@@ -108,11 +115,13 @@ impl Seats {
             "The account doesn't have enough balance"
         );
         user.balance.0 -= amount;
+        self.internal_on_balance_change(account_id, user.balance.0 + amount, user.balance.0);
 
         self.accounts.insert(account_id, &user);
 
         require!(self.total_supply >= amount, "Total supply underflow");
         self.total_supply -= amount;
+        self.internal_checkpoint_supply();
     }
 
     pub fn internal_transfer(
@@ -134,7 +143,7 @@ impl Seats {
             // normal transfer
             Some(receiver) => {
                 self.internal_user_withdraw(sender_id, sender, amount);
-                self.internal_user_deposit(receiver_id, receiver, amount, false);
+                self.internal_user_deposit(receiver_id, receiver, amount, None);
                 events::FtTransfer {
                     old_owner_id: sender_id,
                     new_owner_id: receiver_id,
@@ -151,6 +160,25 @@ impl Seats {
         }
     }
 
+    /// Keeps the count of holders, the balance history and the delegated
+    /// votes in sync with a change of an account's balance.
+    pub fn internal_on_balance_change(
+        &mut self,
+        account_id: &AccountId,
+        previous_balance: Balance,
+        balance: Balance,
+    ) {
+        match (previous_balance > 0, balance > 0) {
+            (false, true) => self.holders += 1,
+            (true, false) => self.holders -= 1,
+            _ => {}
+        }
+        self.internal_checkpoint_balance(account_id, balance);
+        if let Some(delegate_id) = self.delegation.delegates.get(account_id) {
+            self.internal_move_votes(&delegate_id, previous_balance, balance);
+        }
+    }
+
     pub fn internal_register_account(
         &mut self,
         account_id: &AccountId,
@@ -161,6 +189,7 @@ impl Seats {
             previous_account.is_none(),
             "The account is already registered"
         );
+        self.account_ids.insert(account_id);
     }
 
     /// Internal method that returns the amount of burned tokens in a corner case
@@ -219,6 +248,11 @@ impl Seats {
                         // Burns at most what he has on the balance.
                         let burn_amount = std::cmp::min(receiver_balance, unused_amount);
                         receiver.balance.0 -= burn_amount;
+                        self.internal_on_balance_change(
+                            &receiver_id,
+                            receiver_balance,
+                            receiver.balance.0,
+                        );
                         self.accounts.insert(&receiver_id, &receiver);
                         log!("The account of the sender was deleted");
                         events::FtBurn {
@@ -261,10 +295,20 @@ impl Seats {
 
                         // Decrements from the Receiver.
                         receiver.balance.0 -= return_amount;
+                        self.internal_on_balance_change(
+                            &receiver_id,
+                            receiver_balance,
+                            receiver.balance.0,
+                        );
                         self.accounts.insert(&receiver_id, &receiver);
 
                         // Increments from the Sender.
                         sender.balance.0 += return_amount;
+                        self.internal_on_balance_change(
+                            sender_id,
+                            sender.balance.0 - return_amount,
+                            sender.balance.0,
+                        );
 
                         self.accounts.insert(sender_id, &sender);
 
@@ -0,0 +1,357 @@
+//! Proposals that token holders vote on with their delegated votes, and which
+//! call some whitelisted actions once approved.
+//!
+//! The voting power of each account is taken from the end of the last mint
+//! generation that had already ended when the proposal was created, so that
+//! it can't be changed by anything done after the creation.
+
+use crate::{
+    events,
+    types::{self, ProposalAction, VoteKind},
+    Seats,
+};
+use near_sdk::{env, json_types::U64, near_bindgen, require, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Changes the governance configuration, or disables the creation of
+    /// proposals if `null`.
+    ///
+    /// Returns the previous configuration.
+    ///
+    /// ###### Notes
+    ///
+    /// - Proposals that were already created keep their quorum and voting
+    /// period.
+    pub fn change_governance_config(
+        &mut self,
+        config: Option<types::GovernanceConfig>,
+    ) -> Option<types::GovernanceConfig> {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        std::mem::replace(&mut self.governance.config, config)
+    }
+
+    /// Get the governance configuration.
+    pub fn get_governance_config(&self) -> Option<types::GovernanceConfig> {
+        self.governance.config
+    }
+
+    /// Creates a proposal, which can then be voted on.
+    ///
+    /// The caller must have at least the proposal threshold in votes, and
+    /// must attach enough deposit to cover the storage of the proposal. Any
+    /// extra deposit is refunded.
+    ///
+    /// Returns the proposal id.
+    ///
+    /// ### Parameters
+    ///
+    /// - `description`: What the proposal is about.
+    /// - `actions`: What should be called once the proposal is approved, in
+    /// order.
+    ///
+    /// ###### Notes
+    ///
+    /// - Proposals can only be created after the first mint, since the voting
+    /// power is taken from a mint generation that already ended.
+    /// - The actions can't remove every owner.
+    #[payable]
+    pub fn propose(
+        &mut self,
+        description: String,
+        actions: Vec<ProposalAction>,
+    ) -> U64 {
+        self.assert_non_migration();
+
+        let config = self
+            .governance
+            .config
+            .unwrap_or_else(|| env::panic_str("governance not configured"));
+        require!(!actions.is_empty(), "the proposal has no actions");
+        self.internal_assert_keeps_owners(&actions);
+        let snapshot_generation = self
+            .internal_checkpoint_generation()
+            .checked_sub(1)
+            .unwrap_or_else(|| {
+                env::panic_str("proposals can only be created after the first mint")
+            });
+
+        let proposer_id = env::predecessor_account_id();
+        let votes = self.get_votes(proposer_id.clone());
+        require!(
+            votes.0 >= config.proposal_threshold.0,
+            &format!(
+                "account {} has {} votes, below the proposal threshold of {}",
+                proposer_id, votes.0, config.proposal_threshold.0
+            )
+        );
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        let proposal = types::Proposal {
+            proposer_id: proposer_id.clone(),
+            description,
+            actions,
+            created_at: now,
+            snapshot_generation,
+            voting_ends_at: now + types::Timestamp::from_seconds(config.voting_period_seconds),
+            quorum: config.quorum,
+            votes_for: 0.into(),
+            votes_against: 0.into(),
+            votes_abstain: 0.into(),
+            executed: false,
+        };
+
+        let proposal_id = self.governance.proposals.len();
+        common::refund_deposit(|| self.governance.proposals.push(&proposal));
+
+        events::ProposalCreate {
+            proposal_id: proposal_id.into(),
+            proposer_id: &proposer_id,
+            voting_ends_at: proposal.voting_ends_at,
+        }
+        .emit();
+
+        proposal_id.into()
+    }
+
+    /// Votes on an active proposal, with the votes that the caller had at the
+    /// end of the proposal's snapshot generation.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// vote, and any extra deposit is refunded.
+    ///
+    /// Returns the vote.
+    #[payable]
+    pub fn vote(
+        &mut self,
+        proposal_id: U64,
+        kind: VoteKind,
+    ) -> types::Vote {
+        self.assert_non_migration();
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        let mut proposal = self.internal_unwrap_proposal(proposal_id.0);
+        require!(
+            proposal.status(now) == types::ProposalStatus::Active,
+            &format!("proposal {} is not active", proposal_id.0)
+        );
+
+        let account_id = env::predecessor_account_id();
+        let key = (proposal_id.0, account_id.clone());
+        require!(
+            !self.governance.votes.contains_key(&key),
+            &format!(
+                "account {} already voted on proposal {}",
+                account_id, proposal_id.0
+            )
+        );
+
+        let weight = self.get_past_votes(
+            account_id.clone(),
+            types::PastPoint::Generation(proposal.snapshot_generation),
+        );
+        require!(
+            weight.0 > 0,
+            &format!(
+                "account {} had no votes at the snapshot of proposal {}",
+                account_id, proposal_id.0
+            )
+        );
+
+        let tally = match kind {
+            VoteKind::For => &mut proposal.votes_for,
+            VoteKind::Against => &mut proposal.votes_against,
+            VoteKind::Abstain => &mut proposal.votes_abstain,
+        };
+        tally.0 += weight.0;
+
+        let vote = types::Vote { kind, weight };
+        common::refund_deposit(|| {
+            self.governance.votes.insert(&key, &vote);
+            self.governance.proposals.replace(proposal_id.0, &proposal);
+        });
+
+        events::ProposalVote {
+            proposal_id,
+            account_id: &account_id,
+            vote: &vote,
+        }
+        .emit();
+
+        vote
+    }
+
+    /// Executes the actions of an approved proposal, after it's voting
+    /// period.
+    ///
+    /// Can be called by anyone, who must attach enough deposit to cover any
+    /// extra storage. Any extra deposit is refunded.
+    #[payable]
+    pub fn execute_proposal(
+        &mut self,
+        proposal_id: U64,
+    ) {
+        self.assert_non_migration();
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        let mut proposal = self.internal_unwrap_proposal(proposal_id.0);
+        require!(
+            proposal.status(now) == types::ProposalStatus::Succeeded,
+            &format!("proposal {} has not succeeded", proposal_id.0)
+        );
+
+        proposal.executed = true;
+        self.governance.proposals.replace(proposal_id.0, &proposal);
+
+        common::refund_deposit(|| {
+            for action in proposal.actions.clone() {
+                self.internal_execute_action(action);
+            }
+        });
+
+        events::ProposalExecute {
+            proposal_id,
+            account_id: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Get a proposal, with it's current status.
+    pub fn get_proposal(
+        &self,
+        proposal_id: U64,
+    ) -> Option<types::ProposalInfo> {
+        let now = types::Timestamp::from(env::block_timestamp());
+        self.governance
+            .proposals
+            .get(proposal_id.0)
+            .map(|proposal| types::ProposalInfo {
+                id: proposal_id,
+                status: proposal.status(now),
+                proposal,
+            })
+    }
+
+    /// Get the proposals, with their current status.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many proposals to skip.
+    /// - `limit`: How many proposals to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn get_proposals(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<types::ProposalInfo> {
+        let now = types::Timestamp::from(env::block_timestamp());
+        let proposals = &self.governance.proposals;
+
+        crate::page_range(from_index, limit, proposals.len())
+            .filter_map(|id| Some((id, proposals.get(id)?)))
+            .map(|(id, proposal)| types::ProposalInfo {
+                id: id.into(),
+                status: proposal.status(now),
+                proposal,
+            })
+            .collect()
+    }
+
+    /// Get the vote of an account on a proposal.
+    pub fn get_proposal_vote(
+        &self,
+        proposal_id: U64,
+        account_id: AccountId,
+    ) -> Option<types::Vote> {
+        self.governance.votes.get(&(proposal_id.0, account_id))
+    }
+}
+
+impl Seats {
+    pub fn internal_unwrap_proposal(
+        &self,
+        proposal_id: u64,
+    ) -> types::Proposal {
+        self.governance
+            .proposals
+            .get(proposal_id)
+            .unwrap_or_else(|| env::panic_str(&format!("missing proposal {}", proposal_id)))
+    }
+
+    /// Panics if the actions, applied in order to the current owners, would
+    /// remove all of them.
+    fn internal_assert_keeps_owners(
+        &self,
+        actions: &[ProposalAction],
+    ) {
+        let mut changed = std::collections::HashMap::new();
+        let mut owners = self.owners.len();
+        for action in actions {
+            let (owner_id, is_owner) = match action {
+                ProposalAction::AddOwner { owner_id } => (owner_id, true),
+                ProposalAction::RemoveOwner { owner_id } => (owner_id, false),
+                _ => continue,
+            };
+            let was_owner = changed
+                .insert(owner_id, is_owner)
+                .unwrap_or_else(|| self.owners.contains(owner_id));
+            match (was_owner, is_owner) {
+                (false, true) => owners += 1,
+                (true, false) => {
+                    require!(
+                        owners > 1,
+                        &format!("removing owner {} would leave no owners", owner_id)
+                    );
+                    owners -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn internal_execute_action(
+        &mut self,
+        action: ProposalAction,
+    ) {
+        match action {
+            ProposalAction::ChangeVestingSet {
+                name,
+                new_start_date,
+                new_expiration_date,
+                new_reward,
+                new_users_must_claim,
+            } => {
+                self.internal_change_vesting_set(
+                    name,
+                    new_start_date,
+                    new_expiration_date,
+                    new_reward,
+                    new_users_must_claim,
+                );
+            }
+            ProposalAction::ChangeStartTimestamp {
+                new_start_timestamp_seconds,
+            } => {
+                self.internal_change_start_timestamp(new_start_timestamp_seconds);
+            }
+            ProposalAction::AddOwner { owner_id } => {
+                self.owners.insert(&owner_id);
+            }
+            ProposalAction::RemoveOwner { owner_id } => {
+                require!(
+                    self.owners.len() > 1 || !self.owners.contains(&owner_id),
+                    &format!("removing owner {} would leave no owners", owner_id)
+                );
+                self.owners.remove(&owner_id);
+            }
+            ProposalAction::SetMetadata { metadata } => {
+                self.internal_replace_metadata(metadata, env::current_account_id());
+            }
+        }
+    }
+}
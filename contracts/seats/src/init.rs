@@ -1,10 +1,10 @@
 #![allow(clippy::too_many_arguments)]
 
-use crate::{metadata, types, Seats, StorageKey};
+use crate::{metadata, migration, types, Seats, StorageKey};
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet},
+    collections::{LazyOption, LookupMap, TreeMap, UnorderedMap, UnorderedSet, Vector},
     near_bindgen, require,
     serde::{Deserialize, Serialize},
     AccountId,
@@ -79,8 +79,11 @@ impl Seats {
         let mut this = Self {
             owners,
             accounts: LookupMap::new(StorageKey::Accounts),
+            account_ids: UnorderedSet::new(StorageKey::AccountIds),
+            holders: 0,
             total_supply: 0,
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
+            metadata_history: Vector::new(StorageKey::MetadataHistory),
             start_timestamp,
             mint: types::MintInfo {
                 generation: u32::default(),
@@ -88,11 +91,56 @@ impl Seats {
                 last_mint_timestamp: None,
                 vesting: types::Vesting {
                     sets: UnorderedMap::new(StorageKey::VestingAccounts),
+                    receivers: LookupMap::new(StorageKey::VestingReceivers),
+                    requests: LookupMap::new(StorageKey::VestingRequests),
                 },
                 state: types::MintState::Standby,
+                budget: types::MintBudget {
+                    window_seconds: crate::constant_init::DAY,
+                    global_limit: None,
+                    owner_limit: None,
+                    global_usage: types::MintBudgetUsage::default(),
+                    owner_usage: LookupMap::new(StorageKey::MintBudgetOwners),
+                    pending: None,
+                },
             },
             migration_locked: false,
+            paused: types::PauseFlags::default(),
+            migration: types::MigrationInfo {
+                from_version: migration::STATE_VERSION,
+                state: types::MigrationState::Standby,
+                converted_accounts: 0,
+                unconverted_supply: 0,
+            },
+            roles: LookupMap::new(StorageKey::Roles),
+            treasury: None,
+            supply: types::SupplyCounters {
+                claim_balance: 0,
+                locked_claims: TreeMap::new(StorageKey::LockedClaims),
+            },
+            checkpoints: types::Checkpoints {
+                accounts: LookupMap::new(StorageKey::BalanceCheckpoints),
+                total_supply: Vector::new(StorageKey::SupplyCheckpoints),
+                allowances: LookupMap::new(StorageKey::HistoryAllowances),
+            },
+            delegation: types::Delegation {
+                delegates: LookupMap::new(StorageKey::Delegates),
+                votes: LookupMap::new(StorageKey::Votes),
+            },
+            governance: types::Governance {
+                config: None,
+                proposals: Vector::new(StorageKey::Proposals),
+                votes: LookupMap::new(StorageKey::ProposalVotes),
+            },
+            staking: types::Staking {
+                config: types::StakingConfig::default(),
+                total_staked: 0,
+                reward_per_share: 0,
+                stakes: LookupMap::new(StorageKey::Stakes),
+            },
+            streams: Vector::new(StorageKey::Streams),
         };
+        migration::write_state_version(migration::STATE_VERSION);
         this.internal_register_account(&owner_id);
         this
     }
@@ -114,9 +162,6 @@ impl Seats {
         // extra parameters
         set_members: Vec<SetMembers>,
     ) -> Self {
-        use std::collections::HashSet;
-        use types::Timestamp;
-
         let mut this = Self::new(
             owner_id,
             metadata,
@@ -124,46 +169,78 @@ impl Seats {
             start_timestamp_seconds,
         );
 
-        let mut added_members = HashSet::new();
+        this.internal_import_set_members(set_members, false);
 
-        for SetMembers {
-            set,
-            members,
-            reward,
-            start_delay_seconds,
-            expiration_delay_seconds,
-            users_must_claim,
-        } in set_members
-        {
-            let start_date = Timestamp::from_seconds(start_timestamp_seconds)
-                + Timestamp::from_seconds(start_delay_seconds);
-            let expiration_date = start_date + Timestamp::from_seconds(expiration_delay_seconds);
-            let mut user_set = this.internal_prepare_vesting_set(
-                &set,
-                start_date,
-                expiration_date,
-                reward,
-                users_must_claim,
-            );
+        this
+    }
 
-            for (member, percentage) in members {
-                let is_new_member = added_members.insert(member.clone());
+    /// Initializes the SEAT FT contract in a setup phase, in which the vesting
+    /// sets and their members are imported in batches by
+    /// `import_set_members`.
+    ///
+    /// Minting, transfers and other operations are locked until the setup is
+    /// sealed by `seal_setup`.
+    ///
+    /// ### Parameters
+    ///
+    /// The same as [`new()`].
+    #[payable]
+    #[init]
+    pub fn new_staged(
+        owner_id: AccountId,
+        metadata: FungibleTokenMetadata,
+        mint_lock_duration_seconds: u32,
+        start_timestamp_seconds: u32,
+    ) -> Self {
+        let mut this = Self::new(
+            owner_id,
+            metadata,
+            mint_lock_duration_seconds,
+            start_timestamp_seconds,
+        );
+        this.migration_locked = true;
+        this.migration.state = types::MigrationState::Setup;
+        this
+    }
 
-                if is_new_member {
-                    this.internal_register_account(&member);
-                }
+    /// Imports a batch of vesting sets and their members, during the setup
+    /// phase.
+    ///
+    /// Members that are not yet registered get registered. Sets that were
+    /// already imported by a previous batch keep their information, and only
+    /// get the new members added into them.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the new
+    /// sets, accounts and members, and any extra deposit is refunded.
+    ///
+    /// ### Parameters
+    ///
+    /// - `set_members`: List for vesting sets information to be added.
+    #[payable]
+    pub fn import_set_members(
+        &mut self,
+        set_members: Vec<SetMembers>,
+    ) {
+        self.assert_owner();
+        self.assert_setup_phase();
 
-                this.internal_add_vesting_user(&set, &mut user_set, member, percentage);
-            }
+        common::refund_deposit(|| self.internal_import_set_members(set_members.clone(), true));
+    }
 
-            let previous = this.mint.vesting.sets.insert(&set, &user_set);
-            require!(
-                previous.is_none(),
-                &format!("set {} is already registered", &set.0)
-            );
+    /// Ends the setup phase, unlocking the contract.
+    ///
+    /// Every set must have it's member percentages adding up to, at most,
+    /// 100%.
+    pub fn seal_setup(&mut self) {
+        self.assert_owner();
+        self.assert_setup_phase();
+
+        for (_name, user_set) in self.mint.vesting.sets.iter() {
+            user_set.info.total_user_percentages.check();
         }
 
-        this
+        self.migration.state = types::MigrationState::Standby;
+        self.migration_locked = false;
     }
 
     /// Initializes the SEAT FT contract with some pre-determined initial
@@ -199,3 +276,65 @@ impl Seats {
         )
     }
 }
+
+impl Seats {
+    pub fn assert_setup_phase(&self) {
+        require!(
+            matches!(self.migration.state, types::MigrationState::Setup),
+            "setup phase not in progress"
+        );
+    }
+
+    /// Registers the vesting sets and their members, also registering the
+    /// members that are not yet registered.
+    ///
+    /// If `extend_existing` is `true`, sets that are already registered get
+    /// the members added into them. Otherwise they cause a panic.
+    pub fn internal_import_set_members(
+        &mut self,
+        set_members: Vec<SetMembers>,
+        extend_existing: bool,
+    ) {
+        use types::Timestamp;
+
+        for SetMembers {
+            set,
+            members,
+            reward,
+            start_delay_seconds,
+            expiration_delay_seconds,
+            users_must_claim,
+        } in set_members
+        {
+            let existing = self.mint.vesting.sets.get(&set);
+            require!(
+                existing.is_none() || extend_existing,
+                &format!("set {} is already registered", &set.0)
+            );
+
+            let mut user_set = existing.unwrap_or_else(|| {
+                let start_date =
+                    self.start_timestamp + Timestamp::from_seconds(start_delay_seconds);
+                let expiration_date =
+                    start_date + Timestamp::from_seconds(expiration_delay_seconds);
+                self.internal_prepare_vesting_set(
+                    &set,
+                    start_date,
+                    expiration_date,
+                    reward,
+                    users_must_claim,
+                )
+            });
+
+            for (member, percentage) in members {
+                if !self.accounts.contains_key(&member) {
+                    self.internal_register_account(&member);
+                }
+
+                self.internal_add_vesting_user(&set, &mut user_set, member, percentage);
+            }
+
+            self.mint.vesting.sets.insert(&set, &user_set);
+        }
+    }
+}
@@ -3,25 +3,51 @@
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{LazyOption, LookupMap, UnorderedSet},
+    collections::{LazyOption, LookupMap, UnorderedSet, Vector},
     log, near_bindgen, AccountId, Balance, BorshStorageKey, PanicOnDefault,
 };
 
+pub mod accounts;
+pub mod checkpoints;
 pub mod claim;
 pub mod constant_init;
+pub mod delegation;
 pub mod event;
+pub mod events;
 pub mod fungible_token;
+pub mod governance;
 pub mod init;
 pub mod metadata;
 pub mod migration;
 pub mod owners;
+pub mod pause;
+pub mod revocation;
+pub mod roles;
+pub mod staking;
 pub mod storage_costs;
+pub mod streams;
+pub mod supply;
 pub mod types;
 pub mod version;
 pub mod vesting;
+pub mod vesting_requests;
 
 pub const DECIMAL_PLACE_PADDING: u128 = u128::pow(10, metadata::DECIMALS as u32);
 
+/// How many items the paginated views show, if no `limit` is given.
+pub const DEFAULT_PAGE_LIMIT: u16 = 100;
+
+/// The indexes of a page from a collection of `len` items.
+pub fn page_range(
+    from_index: Option<near_sdk::json_types::U64>,
+    limit: Option<u16>,
+    len: u64,
+) -> std::ops::Range<u64> {
+    let from_index = std::cmp::min(from_index.map(|index| index.0).unwrap_or_default(), len);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as u64;
+    from_index..std::cmp::min(from_index.saturating_add(limit), len)
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Seats {
@@ -32,12 +58,22 @@ pub struct Seats {
     /// [`AccountId`] -> [`types::User`].
     pub accounts: LookupMap<AccountId, types::User>,
 
+    /// Every account registered in [`Self::accounts`], so they can be
+    /// enumerated.
+    pub account_ids: UnorderedSet<AccountId>,
+
+    /// How many registered accounts have a positive balance.
+    pub holders: u64,
+
     /// Total supply of all of the $SEAT tokens.
     pub total_supply: Balance,
 
     /// Metadata for this fungible token contract.
     pub metadata: LazyOption<FungibleTokenMetadata>,
 
+    /// Previous versions of the metadata, from the oldest to the newest.
+    pub metadata_history: Vector<types::MetadataVersion>,
+
     /// The timestmap after which the `start_mint` function will be enabled.
     pub start_timestamp: types::Timestamp,
 
@@ -46,6 +82,38 @@ pub struct Seats {
 
     /// Whether the contract is locked, for migration purposes.
     pub migration_locked: bool,
+
+    /// Which features are paused.
+    pub paused: types::PauseFlags,
+
+    /// Information related to state migrations.
+    pub migration: types::MigrationInfo,
+
+    /// Accounts that have been granted some role, besides the owners.
+    ///
+    /// [`types::Role`] -> [`AccountId`] set.
+    pub roles: LookupMap<types::Role, UnorderedSet<AccountId>>,
+
+    /// The account that receives the shares revoked from vesting members.
+    pub treasury: Option<AccountId>,
+
+    /// Aggregate counters used to break down the supply.
+    pub supply: types::SupplyCounters,
+
+    /// The history of the balances and of the total supply.
+    pub checkpoints: types::Checkpoints,
+
+    /// The voting power delegated by the holders.
+    pub delegation: types::Delegation,
+
+    /// Proposals voted on by the token holders.
+    pub governance: types::Governance,
+
+    /// The staked tokens, and how the stakers get rewarded.
+    pub staking: types::Staking,
+
+    /// Every token stream, by their id.
+    pub streams: Vector<types::Stream>,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -55,6 +123,25 @@ enum StorageKey {
     VestingAccounts,
     VestingAccounts2 { set_name: types::SetName },
     Metadata,
+    MintBudgetOwners,
+    Roles,
+    RoleMembers { role: types::Role },
+    AccountIds,
+    MetadataHistory,
+    VestingReceivers,
+    VestingRequests,
+    LockedClaims,
+    BalanceCheckpoints,
+    AccountCheckpoints { account_id: AccountId },
+    SupplyCheckpoints,
+    Delegates,
+    Votes,
+    AccountVotes { account_id: AccountId },
+    Proposals,
+    ProposalVotes,
+    Stakes,
+    Streams,
+    HistoryAllowances,
 }
 
 impl Seats {
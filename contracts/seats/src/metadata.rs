@@ -1,8 +1,12 @@
-use crate::Seats;
+use crate::{events, types, Seats};
 use near_contract_standards::fungible_token::metadata::{
     FungibleTokenMetadata, FungibleTokenMetadataProvider,
 };
-use near_sdk::{json_types::Base64VecU8, near_bindgen};
+use near_sdk::{
+    env,
+    json_types::{Base64VecU8, U64},
+    near_bindgen, require, AccountId,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::SeatsContract;
@@ -61,3 +65,139 @@ impl FungibleTokenMetadataProvider for Seats {
         self.metadata.get().unwrap()
     }
 }
+
+#[near_bindgen]
+impl Seats {
+    /// Replaces the token metadata.
+    ///
+    /// The caller must be an owner or have the Metadata role, and must attach
+    /// enough deposit to cover the storage of the previous version, which is
+    /// kept in the history. Any extra deposit is refunded.
+    ///
+    /// ### Parameters
+    ///
+    /// - `metadata`: The new metadata. The `decimals` can't be changed.
+    #[payable]
+    pub fn set_metadata(
+        &mut self,
+        metadata: FungibleTokenMetadata,
+    ) {
+        self.internal_update_metadata(|current| *current = metadata.clone());
+    }
+
+    /// Replaces the token icon.
+    ///
+    /// The same requirements of `set_metadata` also apply.
+    ///
+    /// ### Parameters
+    ///
+    /// - `icon`: The new icon, as a data URL.
+    #[payable]
+    pub fn set_metadata_icon(
+        &mut self,
+        icon: Option<String>,
+    ) {
+        self.internal_update_metadata(|current| current.icon = icon.clone());
+    }
+
+    /// Replaces the token reference, and it's hash.
+    ///
+    /// The same requirements of `set_metadata` also apply.
+    ///
+    /// ### Parameters
+    ///
+    /// - `reference`: A link to a JSON file with supplementary details on
+    /// the token.
+    /// - `reference_hash`: The base64-encoded sha256 hash of the JSON file.
+    #[payable]
+    pub fn set_metadata_reference(
+        &mut self,
+        reference: Option<String>,
+        reference_hash: Option<Base64VecU8>,
+    ) {
+        self.internal_update_metadata(|current| {
+            current.reference = reference.clone();
+            current.reference_hash = reference_hash.clone();
+        });
+    }
+
+    /// Replaces the token name.
+    ///
+    /// The same requirements of `set_metadata` also apply.
+    ///
+    /// ### Parameters
+    ///
+    /// - `name`: The new human-readable name of the token.
+    #[payable]
+    pub fn set_metadata_name(
+        &mut self,
+        name: String,
+    ) {
+        self.internal_update_metadata(|current| current.name = name.clone());
+    }
+
+    /// Get the previous versions of the token metadata, from the oldest to
+    /// the newest.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many versions to skip.
+    /// - `limit`: How many versions to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn get_metadata_history(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<types::MetadataVersion> {
+        crate::page_range(from_index, limit, self.metadata_history.len())
+            .filter_map(|index| self.metadata_history.get(index))
+            .collect()
+    }
+}
+
+impl Seats {
+    /// Updates the token metadata, keeping the previous version in the
+    /// history.
+    fn internal_update_metadata(
+        &mut self,
+        mut update: impl FnMut(&mut FungibleTokenMetadata),
+    ) {
+        self.assert_role(types::Role::Metadata);
+        self.assert_non_migration();
+
+        common::refund_deposit(|| {
+            let mut metadata = self.metadata.get().unwrap();
+            update(&mut metadata);
+            self.internal_replace_metadata(metadata, env::predecessor_account_id());
+        })
+    }
+
+    /// Replaces the token metadata, keeping the previous version in the
+    /// history, without checking the caller.
+    pub fn internal_replace_metadata(
+        &mut self,
+        metadata: FungibleTokenMetadata,
+        account_id: AccountId,
+    ) {
+        let previous = self.metadata.get().unwrap();
+
+        check(&metadata);
+        require!(
+            metadata.decimals == previous.decimals,
+            "metadata.decimals cannot be changed"
+        );
+
+        self.metadata.set(&metadata);
+        self.metadata_history.push(&types::MetadataVersion {
+            metadata: previous,
+            replaced_at: env::block_timestamp().into(),
+            replaced_by: account_id.clone(),
+        });
+
+        events::MetadataUpdate {
+            account_id: &account_id,
+            version: self.metadata_history.len(),
+        }
+        .emit();
+    }
+}
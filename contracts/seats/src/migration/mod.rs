@@ -0,0 +1,194 @@
+use crate::{types, Seats};
+use near_sdk::{
+    borsh::{BorshDeserialize, BorshSerialize},
+    env, near_bindgen, require, AccountId,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+pub mod relocation;
+pub mod upgrade;
+pub mod v0;
+
+/// The version of the contract's state layout.
+///
+/// This must be increased whenever the layout of [`Seats`], [`types::User`],
+/// [`types::MintInfo`] or of the vesting sets changes. The older layouts are
+/// then kept in their own module (such as [`v0`]), together with their
+/// conversion into the current layout.
+pub const STATE_VERSION: u16 = 1;
+
+/// Storage key that holds the state version.
+///
+/// Contracts that were deployed before this key existed have the version `0`.
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+#[near_bindgen]
+impl Seats {
+    pub fn force_start_migration(&mut self) {
+        self.assert_owner();
+        self.migration_locked = true;
+    }
+
+    pub fn force_end_migration(&mut self) {
+        self.assert_owner();
+        match self.migration.state {
+            types::MigrationState::Standby => {}
+            types::MigrationState::Import { .. } => {
+                env::panic_str("the import must be finished by finish_import")
+            }
+            types::MigrationState::Setup => {
+                env::panic_str("the setup must be sealed by seal_setup")
+            }
+        }
+        require!(
+            self.migration.unconverted_supply == 0,
+            &format!(
+                "{} raw tokens are still in accounts not converted by migrate_accounts",
+                self.migration.unconverted_supply
+            )
+        );
+        self.migration_locked = false;
+    }
+
+    /// Migrates the contract's state from an older layout into the current
+    /// one.
+    ///
+    /// Must be called by the contract itself, right after the new code got
+    /// deployed.
+    ///
+    /// The root state and the vesting sets are converted right away, while the
+    /// accounts are converted in pages by [`Self::migrate_accounts()`]. The
+    /// contract stays locked for migration until an owner calls
+    /// `force_end_migration`.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let version = read_state_version();
+        require!(
+            version <= STATE_VERSION,
+            &format!(
+                "cannot migrate from the state version {} into the older version {}",
+                version, STATE_VERSION
+            )
+        );
+
+        let this = match version {
+            0 => Self::from(
+                env::state_read::<v0::Seats>()
+                    .unwrap_or_else(|| env::panic_str("missing contract state")),
+            ),
+            // already at the current version
+            _ => env::state_read::<Self>()
+                .unwrap_or_else(|| env::panic_str("missing contract state")),
+        };
+
+        write_state_version(STATE_VERSION);
+        this
+    }
+
+    /// Converts the given accounts into the current layout, and indexes them
+    /// so they can be enumerated.
+    ///
+    /// Accounts that are not registered or that are already at the current
+    /// layout are skipped.
+    ///
+    /// Returns how many accounts got converted.
+    pub fn migrate_accounts(
+        &mut self,
+        account_ids: Vec<AccountId>,
+    ) -> u32 {
+        self.assert_owner();
+        require!(self.migration_locked, "migration not in progress");
+
+        let now = types::Timestamp::from(env::block_timestamp());
+        let mut converted = 0;
+        for account_id in account_ids {
+            let key = lookup_map_key(crate::StorageKey::Accounts, &account_id);
+            let bytes = match env::storage_read(&key) {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            self.account_ids.insert(&account_id);
+            let user = match self.migration.from_version {
+                0 => v0::convert_user(&bytes),
+                _ => None,
+            };
+            if let Some(user) = user {
+                self.migration.unconverted_supply -= user.balance.0 + user.claim_balance.0;
+                self.migration.converted_accounts += 1;
+                self.supply.add_user(&user, now);
+                self.internal_on_balance_change(&account_id, 0, user.balance.0);
+                env::storage_write(&key, &user.try_to_vec().unwrap());
+                converted += 1;
+            }
+        }
+        converted
+    }
+
+    /// Get which migration procedure is happening, if any.
+    pub fn get_migration_state(&self) -> types::MigrationState {
+        self.migration.state.clone()
+    }
+
+    /// Get how many accounts were converted into the current layout, and how
+    /// many raw SEAT tokens are still in accounts that were not converted.
+    ///
+    /// The migration can only be ended once no tokens are left unconverted.
+    pub fn get_accounts_migration(&self) -> types::AccountsMigration {
+        types::AccountsMigration {
+            converted_accounts: self.migration.converted_accounts.into(),
+            unconverted_supply: self.migration.unconverted_supply.into(),
+        }
+    }
+
+    /// Get the version of the contract's state layout.
+    pub fn get_state_version(&self) -> u16 {
+        read_state_version()
+    }
+}
+
+impl Seats {
+    pub fn assert_non_migration(&self) {
+        require!(
+            !self.migration_locked,
+            "the contract is locked for migration"
+        );
+    }
+}
+
+pub fn read_state_version() -> u16 {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|bytes| {
+            u16::try_from_slice(&bytes)
+                .unwrap_or_else(|_err| env::panic_str("Cannot deserialize the state version"))
+        })
+        .unwrap_or(0)
+}
+
+pub fn write_state_version(version: u16) {
+    env::storage_write(STATE_VERSION_KEY, &version.try_to_vec().unwrap());
+}
+
+/// The storage key of an entry from a [`near_sdk::collections::LookupMap`].
+fn lookup_map_key(
+    prefix: crate::StorageKey,
+    key: &impl BorshSerialize,
+) -> Vec<u8> {
+    let mut storage_key = prefix.try_to_vec().unwrap();
+    storage_key.extend(key.try_to_vec().unwrap());
+    storage_key
+}
+
+/// The storage key of a value, by it's index, from a
+/// [`near_sdk::collections::UnorderedMap`].
+fn unordered_map_value_key(
+    prefix: crate::StorageKey,
+    index: u64,
+) -> Vec<u8> {
+    let mut storage_key = prefix.try_to_vec().unwrap();
+    storage_key.push(b'v');
+    storage_key.extend(index.to_le_bytes());
+    storage_key
+}
@@ -0,0 +1,458 @@
+//! Export of the contract's state in pages, and it's import into a freshly
+//! initialized contract, so the token can be moved into another account.
+//!
+//! The owners are exported by `get_owners` and added by `add_owner`, the
+//! roles are exported by `get_role_members` and granted by `grant_role`, and
+//! the current metadata is given when the new contract is initialized. The
+//! stakes are exported with their accounts, and the streams by `get_streams`.
+//!
+//! The history of the balances, of the total supply and of the votes is not
+//! relocated, and starts again from the import. Proposals are also not
+//! relocated, so the export fails while any of them can still be voted on or
+//! executed.
+
+use crate::{
+    types::{self, MigrationState, RewardPercentage},
+    Seats,
+};
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    near_bindgen, require,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Get the registered accounts and their balances.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many accounts to skip.
+    /// - `limit`: How many accounts to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn export_accounts(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<types::ExportedAccount> {
+        let account_ids = self.account_ids.as_vector();
+        crate::page_range(from_index, limit, account_ids.len())
+            .filter_map(|index| account_ids.get(index))
+            .map(|account_id| types::ExportedAccount {
+                user: self.internal_unwrap_user(&account_id),
+                delegate_id: self.delegation.delegates.get(&account_id),
+                stake: self.staking.stakes.get(&account_id),
+                account_id,
+            })
+            .collect()
+    }
+
+    /// Get the vesting sets and their information.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many sets to skip.
+    /// - `limit`: How many sets to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn export_vesting_sets(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<(types::SetName, types::VestingUserSetInfo)> {
+        let names = self.mint.vesting.sets.keys_as_vector();
+        let user_sets = self.mint.vesting.sets.values_as_vector();
+        crate::page_range(from_index, limit, names.len())
+            .filter_map(|index| Some((names.get(index)?, user_sets.get(index)?.info)))
+            .collect()
+    }
+
+    /// Get the members of a vesting set, with their receivers and pending
+    /// requests.
+    ///
+    /// ### Parameters
+    ///
+    /// - `set`: The set name.
+    /// - `from_index`: How many members to skip.
+    /// - `limit`: How many members to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn export_vesting_members(
+        &self,
+        set: types::SetName,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<types::ExportedVestingMember> {
+        let members = self.internal_unwrap_vesting_set(&set).accounts;
+        let account_ids = members.keys_as_vector();
+        let percentages = members.values_as_vector();
+        crate::page_range(from_index, limit, account_ids.len())
+            .filter_map(|index| Some((account_ids.get(index)?, percentages.get(index)?)))
+            .map(|(account_id, percentage)| {
+                let key = (set.clone(), account_id.clone());
+                types::ExportedVestingMember {
+                    receiver_id: self.mint.vesting.receivers.get(&key),
+                    request: self.mint.vesting.requests.get(&key),
+                    account_id,
+                    percentage,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the mint information, the total supply and the settings that are
+    /// not specific to an account.
+    ///
+    /// Fails while a proposal can still be voted on or executed.
+    pub fn export_mint_info(&self) -> types::ExportedMintInfo {
+        let now = types::Timestamp::from(env::block_timestamp());
+        for (proposal_id, proposal) in self.governance.proposals.iter().enumerate() {
+            require!(
+                !matches!(
+                    proposal.status(now),
+                    types::ProposalStatus::Active | types::ProposalStatus::Succeeded
+                ),
+                &format!("proposal {} cannot be relocated", proposal_id)
+            );
+        }
+
+        types::ExportedMintInfo {
+            start_timestamp: self.start_timestamp,
+            generation: self.mint.generation,
+            lock_duration_seconds: self.mint.lock_duration_seconds,
+            last_mint_timestamp: self.mint.last_mint_timestamp,
+            budget: self.get_mint_budget(),
+            total_supply: self.total_supply.into(),
+            treasury: self.treasury.clone(),
+            governance: self.governance.config,
+            staking: types::ExportedStaking {
+                config: self.staking.config.clone(),
+                total_staked: self.staking.total_staked.into(),
+                reward_per_share: self.staking.reward_per_share.into(),
+            },
+        }
+    }
+
+    /// Starts importing the state exported from another contract.
+    ///
+    /// The contract must have been freshly initialized, and it stays locked
+    /// until `finish_import` is called.
+    ///
+    /// ### Parameters
+    ///
+    /// - `mint_info`: The mint information, as exported by
+    /// `export_mint_info`.
+    ///
+    /// ###### Notes
+    ///
+    /// - The treasury must be imported as an account, and the staking reward
+    /// set as a vesting set, before the import is finished.
+    /// - The storage for the imported state is charged from the deposit
+    /// attached to each import call.
+    pub fn start_import(
+        &mut self,
+        mint_info: types::ExportedMintInfo,
+    ) {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        require!(
+            self.total_supply == 0
+                && self.mint.generation == 0
+                && self.mint.vesting.sets.is_empty(),
+            "the contract must be freshly initialized"
+        );
+
+        let types::ExportedMintInfo {
+            start_timestamp,
+            generation,
+            lock_duration_seconds,
+            last_mint_timestamp,
+            budget,
+            total_supply,
+            treasury,
+            governance,
+            staking,
+        } = mint_info;
+
+        self.start_timestamp = start_timestamp;
+        self.mint.generation = generation;
+        self.mint.lock_duration_seconds = lock_duration_seconds;
+        self.mint.last_mint_timestamp = last_mint_timestamp;
+        self.mint.budget.set_config(budget);
+        self.treasury = treasury;
+        self.governance.config = governance;
+        self.staking.config = staking.config;
+        self.staking.total_staked = staking.total_staked.0;
+        self.staking.reward_per_share = staking.reward_per_share.0;
+
+        self.migration_locked = true;
+        self.migration.state = MigrationState::Import {
+            expected_total_supply: total_supply,
+            imported_total_supply: 0.into(),
+        };
+    }
+
+    /// Imports a batch of accounts, as exported by `export_accounts`.
+    ///
+    /// Accounts that are already registered get their balances and stakes
+    /// replaced.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// accounts, and any extra deposit is refunded.
+    #[payable]
+    pub fn import_accounts(
+        &mut self,
+        accounts: Vec<types::ExportedAccount>,
+    ) {
+        self.assert_owner();
+        let mut imported = self.internal_unwrap_import();
+        let now = types::Timestamp::from(env::block_timestamp());
+
+        common::refund_deposit(|| {
+            for types::ExportedAccount {
+                account_id,
+                user,
+                delegate_id,
+                stake,
+            } in &accounts
+            {
+                let previous = self.accounts.insert(account_id, user);
+                self.account_ids.insert(account_id);
+
+                if let Some(previous) = previous {
+                    imported -= previous.balance.0 + previous.claim_balance.0;
+                    self.supply.remove_user(&previous, now);
+                    self.internal_on_balance_change(account_id, previous.balance.0, 0);
+                }
+                let previous_stake = match stake {
+                    Some(stake) => self.staking.stakes.insert(account_id, stake),
+                    None => self.staking.stakes.remove(account_id),
+                };
+                if let Some(previous_stake) = previous_stake {
+                    imported -= previous_stake.staked.0 + previous_stake.unstaking.0;
+                }
+
+                self.supply.add_user(user, now);
+                self.internal_on_balance_change(account_id, 0, user.balance.0);
+                self.internal_delegate(account_id, delegate_id.clone());
+                let staked = stake
+                    .as_ref()
+                    .map(|stake| stake.staked.0 + stake.unstaking.0)
+                    .unwrap_or_default();
+                imported = imported
+                    .checked_add(user.balance.0)
+                    .and_then(|imported| imported.checked_add(user.claim_balance.0))
+                    .and_then(|imported| imported.checked_add(staked))
+                    .unwrap_or_else(|| env::panic_str("Total supply overflow"));
+            }
+        });
+
+        self.internal_set_import(imported);
+    }
+
+    /// Imports a batch of vesting sets, as exported by
+    /// `export_vesting_sets`.
+    ///
+    /// The sets start without members, and their `total_user_percentages` is
+    /// recalculated as their members get imported.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// sets, and any extra deposit is refunded.
+    #[payable]
+    pub fn import_vesting_sets(
+        &mut self,
+        sets: Vec<(types::SetName, types::VestingUserSetInfo)>,
+    ) {
+        self.assert_owner();
+        self.internal_unwrap_import();
+
+        common::refund_deposit(|| {
+            for (name, info) in sets.clone() {
+                let mut user_set = self.internal_prepare_vesting_set(
+                    &name,
+                    info.start_date,
+                    info.expiration_date,
+                    info.reward,
+                    info.users_must_claim,
+                );
+                user_set.info = types::VestingUserSetInfo {
+                    total_user_percentages: RewardPercentage::default(),
+                    ..info
+                };
+
+                let previous = self.mint.vesting.sets.insert(&name, &user_set);
+                require!(
+                    previous.is_none(),
+                    &format!("set {} is already registered", &name.0)
+                );
+            }
+        });
+    }
+
+    /// Imports a batch of members into a vesting set, as exported by
+    /// `export_vesting_members`.
+    ///
+    /// The members and their receivers must have already been imported as
+    /// accounts.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// members, and any extra deposit is refunded.
+    #[payable]
+    pub fn import_vesting_members(
+        &mut self,
+        set: types::SetName,
+        members: Vec<types::ExportedVestingMember>,
+    ) {
+        self.assert_owner();
+        self.internal_unwrap_import();
+
+        let mut user_set = self.internal_unwrap_vesting_set(&set);
+        common::refund_deposit(|| {
+            for member in members.clone() {
+                let key = (set.clone(), member.account_id.clone());
+                self.internal_add_vesting_user(
+                    &set,
+                    &mut user_set,
+                    member.account_id,
+                    member.percentage,
+                );
+                if let Some(receiver_id) = member.receiver_id {
+                    self.internal_assert_registered(&receiver_id);
+                    self.internal_reserve_claim_bucket(&set, &key.1, &receiver_id);
+                    self.mint.vesting.receivers.insert(&key, &receiver_id);
+                }
+                if let Some(request) = member.request {
+                    self.mint.vesting.requests.insert(&key, &request);
+                }
+            }
+            self.mint.vesting.sets.insert(&set, &user_set);
+        });
+    }
+
+    /// Imports a batch of previous metadata versions, as exported by
+    /// `get_metadata_history`, from the oldest to the newest.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// versions, and any extra deposit is refunded.
+    #[payable]
+    pub fn import_metadata_history(
+        &mut self,
+        versions: Vec<types::MetadataVersion>,
+    ) {
+        self.assert_owner();
+        self.internal_unwrap_import();
+
+        common::refund_deposit(|| {
+            for version in &versions {
+                self.metadata_history.push(version);
+            }
+        });
+    }
+
+    /// Imports a batch of streams, as exported by `get_streams`, in the order
+    /// of their ids.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// streams, and any extra deposit is refunded.
+    #[payable]
+    pub fn import_streams(
+        &mut self,
+        streams: Vec<types::StreamInfo>,
+    ) {
+        self.assert_owner();
+        let mut imported = self.internal_unwrap_import();
+
+        common::refund_deposit(|| {
+            for types::StreamInfo { id, stream, .. } in &streams {
+                require!(
+                    id.0 == self.streams.len(),
+                    &format!(
+                        "stream {} is out of order, the next one is {}",
+                        id.0,
+                        self.streams.len()
+                    )
+                );
+                self.streams.push(stream);
+                imported = imported
+                    .checked_add(stream.held())
+                    .unwrap_or_else(|| env::panic_str("Total supply overflow"));
+            }
+        });
+
+        self.internal_set_import(imported);
+    }
+
+    /// Finishes the import, unlocking the contract.
+    ///
+    /// The tokens of the imported accounts and streams must match the total
+    /// supply of the exporting contract.
+    pub fn finish_import(&mut self) {
+        self.assert_owner();
+        let imported = self.internal_unwrap_import();
+
+        let expected = match self.migration.state {
+            MigrationState::Import {
+                expected_total_supply,
+                ..
+            } => expected_total_supply.0,
+            _ => unreachable!(),
+        };
+        require!(
+            imported == expected,
+            &format!(
+                "the imported accounts and streams have {} raw tokens, but the total supply is {}",
+                imported, expected
+            )
+        );
+
+        if let Some(treasury_id) = &self.treasury {
+            require!(
+                self.accounts.contains_key(treasury_id),
+                &format!("the treasury {} was not imported", treasury_id)
+            );
+        }
+        if let Some(reward_set) = &self.staking.config.reward_set {
+            require!(
+                self.mint.vesting.sets.get(reward_set).is_some(),
+                &format!("the staking reward set {} was not imported", reward_set.0)
+            );
+        }
+
+        self.total_supply = imported;
+        self.internal_checkpoint_supply();
+        self.migration.state = MigrationState::Standby;
+        self.migration_locked = false;
+    }
+}
+
+impl Seats {
+    /// Returns the total of tokens from the accounts and streams imported so
+    /// far.
+    ///
+    /// Panics if the import is not in progress.
+    fn internal_unwrap_import(&self) -> u128 {
+        match self.migration.state {
+            MigrationState::Import {
+                imported_total_supply,
+                ..
+            } if self.migration_locked => imported_total_supply.0,
+            _ => env::panic_str("import not in progress"),
+        }
+    }
+
+    fn internal_set_import(
+        &mut self,
+        imported: u128,
+    ) {
+        if let MigrationState::Import {
+            imported_total_supply,
+            ..
+        } = &mut self.migration.state
+        {
+            *imported_total_supply = U128(imported);
+        }
+    }
+}
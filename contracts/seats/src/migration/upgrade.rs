@@ -0,0 +1,174 @@
+//! Upgrades of the contract's own code, followed by the state migration.
+
+use crate::{types::Role, Seats};
+use near_sdk::{
+    env, json_types::Base58CryptoHash, log, near_bindgen, require, serde_json::json, CryptoHash,
+    Gas, Promise, PromiseResult,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+/// Gas reserved for the deployment of the code and for scheduling the calls.
+const GAS_FOR_UPGRADE: Gas = Gas(30_000_000_000_000);
+const GAS_FOR_ON_UPGRADE: Gas = Gas(10_000_000_000_000);
+
+/// Storage key that holds the code staged by `stage_code`.
+const STAGED_CODE_KEY: &[u8] = b"STAGED_CODE";
+
+/// Storage key that holds the hash of the code deployed by the last
+/// successful upgrade.
+const CODE_HASH_KEY: &[u8] = b"CODE_HASH";
+
+#[near_bindgen]
+impl Seats {
+    /// Deploys new code into this contract and then calls it's `migrate`
+    /// method, in the same promise chain.
+    ///
+    /// The wasm code is the raw input of this method, and not a JSON.
+    ///
+    /// The caller must be an owner or have the Migration role, and the
+    /// contract must already be locked for migration.
+    ///
+    /// Returns `true` if the upgrade succeeded. Otherwise the deployment is
+    /// reverted and the contract is unlocked.
+    pub fn upgrade(&mut self) -> Promise {
+        self.assert_role(Role::Migration);
+        let code = env::input().unwrap_or_default();
+        require!(!code.is_empty(), "missing the wasm code");
+        self.internal_upgrade(code)
+    }
+
+    /// Stores wasm code, to be later deployed by `upgrade_staged`.
+    ///
+    /// The wasm code is the raw input of this method, and not a JSON.
+    /// Any previously staged code is replaced.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// code, and any extra deposit is refunded.
+    ///
+    /// Returns the hash of the staged code.
+    #[payable]
+    pub fn stage_code(&mut self) -> Base58CryptoHash {
+        self.assert_role(Role::Migration);
+        let code = env::input().unwrap_or_default();
+        require!(!code.is_empty(), "missing the wasm code");
+        common::refund_deposit(|| env::storage_write(STAGED_CODE_KEY, &code));
+        code_hash(&code).into()
+    }
+
+    /// Deploys the staged code into this contract and then calls it's
+    /// `migrate` method, in the same promise chain.
+    ///
+    /// The same requirements and results of `upgrade` also apply.
+    ///
+    /// ### Parameters
+    ///
+    /// - `code_hash`: The expected hash of the staged code.
+    pub fn upgrade_staged(
+        &mut self,
+        code_hash: Base58CryptoHash,
+    ) -> Promise {
+        self.assert_role(Role::Migration);
+        let code = env::storage_read(STAGED_CODE_KEY)
+            .unwrap_or_else(|| env::panic_str("missing the staged code"));
+        require!(
+            self::code_hash(&code) == CryptoHash::from(code_hash),
+            "the staged code has a different hash"
+        );
+        self.internal_upgrade(code)
+    }
+
+    /// Finishes the upgrade, after the state migration.
+    ///
+    /// On success, the staged code is removed and the code hash is recorded.
+    /// On failure, the contract is unlocked.
+    #[private]
+    pub fn on_upgrade(
+        &mut self,
+        code_hash: Base58CryptoHash,
+    ) -> bool {
+        let code_hash = CryptoHash::from(code_hash);
+        match env::promise_result(0) {
+            PromiseResult::NotReady => env::abort(),
+            PromiseResult::Successful(_) => {
+                if read_staged_code_hash() == Some(code_hash) {
+                    env::storage_remove(STAGED_CODE_KEY);
+                }
+                env::storage_write(CODE_HASH_KEY, &code_hash);
+                log!(
+                    "Upgraded the code to {}",
+                    String::from(&Base58CryptoHash::from(code_hash))
+                );
+                true
+            }
+            PromiseResult::Failed => {
+                self.migration_locked = false;
+                log!("Failed to upgrade the code, the contract got unlocked");
+                false
+            }
+        }
+    }
+
+    /// Get the hash of the code deployed by the last successful upgrade.
+    ///
+    /// Returns `None` if the contract never got upgraded by `upgrade` or
+    /// `upgrade_staged`.
+    ///
+    /// ###### Notes
+    ///
+    /// - This is not necessarily the deployed code, which could have been
+    /// replaced by a deployment from outside of this contract.
+    pub fn get_last_upgrade_code_hash(&self) -> Option<Base58CryptoHash> {
+        env::storage_read(CODE_HASH_KEY)
+            .map(|bytes| {
+                CryptoHash::try_from(bytes.as_slice())
+                    .unwrap_or_else(|_err| env::panic_str("Cannot deserialize the code hash"))
+            })
+            .map(Into::into)
+    }
+
+    /// Get the hash of the code staged by `stage_code`, if any.
+    pub fn get_staged_code_hash(&self) -> Option<Base58CryptoHash> {
+        read_staged_code_hash().map(Into::into)
+    }
+}
+
+impl Seats {
+    fn internal_upgrade(
+        &mut self,
+        code: Vec<u8>,
+    ) -> Promise {
+        require!(
+            self.migration_locked,
+            "the contract must be locked for migration"
+        );
+        require!(
+            env::prepaid_gas() > env::used_gas() + GAS_FOR_UPGRADE + GAS_FOR_ON_UPGRADE,
+            "More gas is required"
+        );
+
+        let code_hash: Base58CryptoHash = code_hash(&code).into();
+        let gas_for_migrate =
+            env::prepaid_gas() - env::used_gas() - GAS_FOR_UPGRADE - GAS_FOR_ON_UPGRADE;
+        let current_account_id = env::current_account_id();
+
+        Promise::new(current_account_id.clone())
+            .deploy_contract(code)
+            .function_call("migrate".to_string(), vec![], 0, gas_for_migrate)
+            .then(Promise::new(current_account_id).function_call(
+                "on_upgrade".to_string(),
+                json!({ "code_hash": code_hash }).to_string().into_bytes(),
+                0,
+                GAS_FOR_ON_UPGRADE,
+            ))
+    }
+}
+
+fn code_hash(code: &[u8]) -> CryptoHash {
+    CryptoHash::try_from(env::sha256(code).as_slice()).unwrap()
+}
+
+fn read_staged_code_hash() -> Option<CryptoHash> {
+    env::storage_read(STAGED_CODE_KEY).map(|code| code_hash(&code))
+}
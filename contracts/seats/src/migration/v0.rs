@@ -0,0 +1,249 @@
+//! State layout from before the state versioning was introduced.
+
+use super::unordered_map_value_key;
+use crate::{types, StorageKey};
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::{LazyOption, LookupMap, TreeMap, UnorderedMap, UnorderedSet, Vector},
+    env, AccountId, Balance,
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Seats {
+    pub owners: UnorderedSet<AccountId>,
+    pub accounts: LookupMap<AccountId, User>,
+    pub total_supply: Balance,
+    pub metadata: LazyOption<FungibleTokenMetadata>,
+    pub start_timestamp: types::Timestamp,
+    pub mint: MintInfo,
+    pub migration_locked: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct User {
+    pub balance: u128,
+    pub claim_balance: u128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MintInfo {
+    pub generation: u32,
+    pub lock_duration_seconds: u32,
+    pub last_mint_timestamp: Option<types::Timestamp>,
+    pub vesting: Vesting,
+    pub state: types::MintState,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Vesting {
+    pub sets: UnorderedMap<types::SetName, VestingUserSet>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct VestingUserSet {
+    pub info: VestingUserSetInfo,
+    pub accounts: UnorderedMap<AccountId, Member>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct VestingUserSetInfo {
+    pub reward: types::RewardAmount,
+    pub total_rewarded: types::RewardAmount,
+    pub generation: u32,
+    pub last_mint_timestamp: types::Timestamp,
+    pub start_date: types::Timestamp,
+    pub expiration_date: types::Timestamp,
+    pub total_user_percentages: types::RewardPercentage,
+    pub users_must_claim: bool,
+}
+
+/// The value of each member from a vesting set, which is the same in the
+/// current layout.
+pub type Member = types::RewardPercentage;
+
+impl From<Seats> for crate::Seats {
+    fn from(old: Seats) -> Self {
+        let Seats {
+            owners,
+            accounts: _,
+            total_supply,
+            metadata,
+            start_timestamp,
+            mint,
+            migration_locked: _,
+        } = old;
+
+        let mut this = Self {
+            owners,
+            // the accounts are converted by `migrate_accounts`
+            accounts: LookupMap::new(StorageKey::Accounts),
+            // the accounts are indexed by `migrate_accounts`
+            account_ids: UnorderedSet::new(StorageKey::AccountIds),
+            // the holders are counted by `migrate_accounts`
+            holders: 0,
+            total_supply,
+            metadata,
+            metadata_history: Vector::new(StorageKey::MetadataHistory),
+            start_timestamp,
+            mint: mint.into(),
+            migration_locked: true,
+            paused: types::PauseFlags::default(),
+            migration: types::MigrationInfo {
+                from_version: 0,
+                state: types::MigrationState::Standby,
+                converted_accounts: 0,
+                unconverted_supply: total_supply,
+            },
+            roles: LookupMap::new(StorageKey::Roles),
+            treasury: None,
+            // the claim balances are counted by `migrate_accounts`
+            supply: types::SupplyCounters {
+                claim_balance: 0,
+                locked_claims: TreeMap::new(StorageKey::LockedClaims),
+            },
+            checkpoints: types::Checkpoints {
+                accounts: LookupMap::new(StorageKey::BalanceCheckpoints),
+                total_supply: Vector::new(StorageKey::SupplyCheckpoints),
+                allowances: LookupMap::new(StorageKey::HistoryAllowances),
+            },
+            delegation: types::Delegation {
+                delegates: LookupMap::new(StorageKey::Delegates),
+                votes: LookupMap::new(StorageKey::Votes),
+            },
+            governance: types::Governance {
+                config: None,
+                proposals: Vector::new(StorageKey::Proposals),
+                votes: LookupMap::new(StorageKey::ProposalVotes),
+            },
+            staking: types::Staking {
+                config: types::StakingConfig::default(),
+                total_staked: 0,
+                reward_per_share: 0,
+                stakes: LookupMap::new(StorageKey::Stakes),
+            },
+            streams: Vector::new(StorageKey::Streams),
+        };
+        // the balances are checkpointed by `migrate_accounts`
+        this.internal_checkpoint_supply();
+        this
+    }
+}
+
+impl From<MintInfo> for types::MintInfo {
+    fn from(old: MintInfo) -> Self {
+        let MintInfo {
+            generation,
+            lock_duration_seconds,
+            last_mint_timestamp,
+            vesting,
+            state,
+        } = old;
+
+        Self {
+            generation,
+            lock_duration_seconds,
+            last_mint_timestamp,
+            vesting: vesting.into(),
+            state,
+            budget: types::MintBudget {
+                window_seconds: crate::constant_init::DAY,
+                global_limit: None,
+                owner_limit: None,
+                global_usage: types::MintBudgetUsage::default(),
+                owner_usage: LookupMap::new(StorageKey::MintBudgetOwners),
+                pending: None,
+            },
+        }
+    }
+}
+
+impl From<Vesting> for types::Vesting {
+    /// Converts every set, in place.
+    ///
+    /// The members of each set keep their layout.
+    fn from(old: Vesting) -> Self {
+        for index in 0..old.sets.len() {
+            let key = unordered_map_value_key(StorageKey::VestingAccounts, index);
+            let bytes =
+                env::storage_read(&key).unwrap_or_else(|| env::panic_str("missing vesting set"));
+            let user_set: types::VestingUserSet = VestingUserSet::try_from_slice(&bytes)
+                .unwrap_or_else(|_err| env::panic_str("Cannot deserialize the vesting set"))
+                .into();
+            env::storage_write(&key, &user_set.try_to_vec().unwrap());
+        }
+
+        // the map itself only holds it's prefixes and lengths,
+        // which are kept the same
+        Self {
+            sets: UnorderedMap::try_from_slice(&old.sets.try_to_vec().unwrap()).unwrap(),
+            receivers: LookupMap::new(StorageKey::VestingReceivers),
+            requests: LookupMap::new(StorageKey::VestingRequests),
+        }
+    }
+}
+
+impl From<VestingUserSet> for types::VestingUserSet {
+    fn from(old: VestingUserSet) -> Self {
+        let VestingUserSet { info, accounts } = old;
+        Self {
+            info: info.into(),
+            accounts: UnorderedMap::try_from_slice(&accounts.try_to_vec().unwrap()).unwrap(),
+        }
+    }
+}
+
+impl From<VestingUserSetInfo> for types::VestingUserSetInfo {
+    fn from(old: VestingUserSetInfo) -> Self {
+        let VestingUserSetInfo {
+            reward,
+            total_rewarded,
+            generation,
+            last_mint_timestamp,
+            start_date,
+            expiration_date,
+            total_user_percentages,
+            users_must_claim,
+        } = old;
+
+        Self {
+            reward,
+            total_rewarded,
+            generation,
+            last_mint_timestamp,
+            start_date,
+            expiration_date,
+            total_user_percentages,
+            users_must_claim,
+            removing: false,
+            sealed: false,
+            requests_need_approval: false,
+            revocable: false,
+            claim_window: None,
+            claim_unlock_date: types::Timestamp::default(),
+        }
+    }
+}
+
+impl From<User> for types::User {
+    fn from(old: User) -> Self {
+        let User {
+            balance,
+            claim_balance,
+        } = old;
+
+        Self {
+            balance: balance.into(),
+            claim_balance: claim_balance.into(),
+            claims: vec![],
+            storage_deposit: 0.into(),
+        }
+    }
+}
+
+/// Converts a serialized account into the current layout.
+///
+/// Returns `None` if the account is not in this layout.
+pub fn convert_user(bytes: &[u8]) -> Option<types::User> {
+    User::try_from_slice(bytes).ok().map(Into::into)
+}
@@ -69,14 +69,17 @@ impl Owners for Seats {
     /// ### Parameters
     ///
     /// - `from_index`: How many owners to skip.
-    /// - `limit`: How many owners to show.
+    /// - `limit`: How many owners to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
     fn get_owners(
         &self,
         from_index: Option<near_sdk::json_types::U128>,
         limit: Option<u16>,
     ) -> Vec<AccountId> {
-        let from_index = from_index.unwrap_or_else(|| 0.into()).0 as usize;
-        let limit = limit.unwrap_or(u16::MAX) as usize;
-        self.owners.iter().skip(from_index).take(limit).collect()
+        let from_index = from_index.map(|index| u64::try_from(index.0).unwrap_or(u64::MAX).into());
+        let owners = self.owners.as_vector();
+        crate::page_range(from_index, limit, owners.len())
+            .filter_map(|index| owners.get(index))
+            .collect()
     }
 }
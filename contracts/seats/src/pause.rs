@@ -0,0 +1,73 @@
+use crate::{
+    events,
+    types::{self, Feature, Role},
+    Seats,
+};
+use near_sdk::{env, near_bindgen, require};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Pauses some features, so their methods can't be called.
+    ///
+    /// The caller must be an owner or have the Guardian role.
+    ///
+    /// ### Parameters
+    ///
+    /// - `features`: The features being paused.
+    pub fn pause(
+        &mut self,
+        features: Vec<Feature>,
+    ) {
+        self.assert_role(Role::Guardian);
+        for feature in &features {
+            self.paused.set(*feature, true);
+        }
+        events::Pause {
+            account_id: &env::predecessor_account_id(),
+            features: &features,
+        }
+        .emit();
+    }
+
+    /// Unpauses some features.
+    ///
+    /// The caller must be an owner or have the Guardian role.
+    ///
+    /// ### Parameters
+    ///
+    /// - `features`: The features being unpaused.
+    pub fn unpause(
+        &mut self,
+        features: Vec<Feature>,
+    ) {
+        self.assert_role(Role::Guardian);
+        for feature in &features {
+            self.paused.set(*feature, false);
+        }
+        events::Unpause {
+            account_id: &env::predecessor_account_id(),
+            features: &features,
+        }
+        .emit();
+    }
+
+    /// Get which features are paused.
+    pub fn get_paused(&self) -> types::PauseFlags {
+        self.paused
+    }
+}
+
+impl Seats {
+    pub fn assert_not_paused(
+        &self,
+        feature: Feature,
+    ) {
+        require!(
+            !self.paused.is_paused(feature),
+            &format!("the {:?} feature is paused", feature)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Revocation of members from revocable vesting sets, with their shares going
+//! to the treasury.
+
+use crate::{
+    events,
+    fungible_token::events as ft_events,
+    types::{self, RewardPercentage},
+    Seats,
+};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Sets the account that receives the shares revoked from vesting
+    /// members, or unsets it if `null`.
+    ///
+    /// The treasury must be registered.
+    pub fn set_treasury(
+        &mut self,
+        account_id: Option<AccountId>,
+    ) {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        if let Some(account_id) = &account_id {
+            self.internal_assert_registered(account_id);
+        }
+        self.treasury = account_id;
+    }
+
+    /// Get the account that receives the shares revoked from vesting members.
+    pub fn get_treasury(&self) -> Option<AccountId> {
+        self.treasury.clone()
+    }
+
+    /// Changes whether members can be revoked from a user set.
+    ///
+    /// Returns the previous value.
+    pub fn change_vesting_set_revocable(
+        &mut self,
+        name: types::SetName,
+        revocable: bool,
+    ) -> bool {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let mut user_set = self.internal_unwrap_vesting_set(&name);
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        let previous = user_set.info.revocable;
+        user_set.info.revocable = revocable;
+        self.mint.vesting.sets.insert(&name, &user_set);
+
+        previous
+    }
+
+    /// Revokes a member from a revocable user set, so it stops receiving
+    /// rewards from it. The member's percentage is given to the treasury,
+    /// which becomes a member of the set if it wasn't yet.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// treasury as a new member, and any extra deposit is refunded.
+    ///
+    /// ### Parameters
+    ///
+    /// - `set`: The set name.
+    /// - `account_id`: The member to be revoked.
+    /// - `claw_back`: Whether the member's tokens from the set that were not
+    /// yet claimed should also be given to the treasury, including the ones
+    /// given to it's reward receiver. Defaults to `false`.
+    ///
+    /// ###### Notes
+    ///
+    /// - Sealed sets can't have members revoked, as their members and
+    /// percentages can no longer change.
+    #[payable]
+    pub fn revoke_vesting_user(
+        &mut self,
+        set: types::SetName,
+        account_id: AccountId,
+        claw_back: Option<bool>,
+    ) -> types::Revocation {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        let treasury_id = self.internal_unwrap_treasury();
+        require!(account_id != treasury_id, "the treasury cannot be revoked");
+        // the receiver is forgotten when the member is removed
+        let receiver_id = self
+            .mint
+            .vesting
+            .receivers
+            .get(&(set.clone(), account_id.clone()));
+
+        let percentage = common::refund_deposit(|| {
+            let mut user_set = self.internal_unwrap_vesting_set(&set);
+            user_set.assert_not_removing(&set);
+            user_set.assert_not_sealed(&set);
+            require!(
+                user_set.info.revocable,
+                &format!("set {} is not revocable", set.0)
+            );
+
+            let percentage = self.internal_remove_vesting_user(&set, &mut user_set, &account_id);
+            match user_set.accounts.get(&treasury_id) {
+                Some(previous) => {
+                    Self::internal_change_vesting_user(
+                        &set,
+                        &mut user_set,
+                        &treasury_id,
+                        RewardPercentage(previous.0 + percentage.0),
+                    );
+                }
+                None => {
+                    self.internal_add_vesting_user(
+                        &set,
+                        &mut user_set,
+                        treasury_id.clone(),
+                        percentage,
+                    );
+                }
+            }
+
+            self.mint.vesting.sets.insert(&set, &user_set);
+            percentage
+        });
+
+        let clawed_back = if claw_back.unwrap_or_default() {
+            let mut clawed_back =
+                self.internal_claw_back(&set, &account_id, &account_id, &treasury_id);
+            if let Some(receiver_id) = &receiver_id {
+                clawed_back +=
+                    self.internal_claw_back(&set, &account_id, receiver_id, &treasury_id);
+            }
+            clawed_back
+        } else {
+            0
+        };
+
+        events::VestingUserRevoke {
+            account_id: &account_id,
+            set: &set,
+            treasury_id: &treasury_id,
+            percentage,
+            clawed_back: clawed_back.into(),
+        }
+        .emit();
+
+        types::Revocation {
+            percentage,
+            clawed_back: clawed_back.into(),
+        }
+    }
+}
+
+impl Seats {
+    pub fn internal_unwrap_treasury(&self) -> AccountId {
+        self.treasury
+            .clone()
+            .unwrap_or_else(|| env::panic_str("treasury not configured"))
+    }
+
+    /// Moves the tokens from the set that were earned by the member, and
+    /// that the account did not yet claim, into the treasury's balance,
+    /// returning how many were moved.
+    fn internal_claw_back(
+        &mut self,
+        set: &types::SetName,
+        member_id: &AccountId,
+        account_id: &AccountId,
+        treasury_id: &AccountId,
+    ) -> u128 {
+        let mut user = match self.accounts.get(account_id) {
+            Some(user) => user,
+            None => return 0,
+        };
+        let now = types::Timestamp::from(env::block_timestamp());
+        let mut amount = 0;
+        for bucket in user.take_set_claims(set, member_id) {
+            self.supply
+                .remove_claim(bucket.unlock_at, bucket.amount.0, now);
+            amount += bucket.amount.0;
+        }
+        if amount == 0 {
+            return 0;
+        }
+        user.claim_balance.0 -= amount;
+        self.accounts.insert(account_id, &user);
+
+        // the tokens were already in the total supply
+        self.total_supply -= amount;
+        let treasury = self.internal_unwrap_user(treasury_id);
+        self.internal_user_deposit(treasury_id, treasury, amount, None);
+
+        ft_events::FtMint {
+            owner_id: treasury_id,
+            amount: &amount.into(),
+            memo: Some("revoke"),
+        }
+        .emit();
+
+        amount
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{types::Role, Seats, StorageKey};
+use near_sdk::{collections::UnorderedSet, env, near_bindgen, require, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Grants a role to an account.
+    ///
+    /// Returns `true` if the role was newly granted.  
+    /// Returns `false` if the account already had the role.
+    ///
+    /// ### Parameters
+    ///
+    /// - `role`: The role being granted.
+    /// - `account_id`: The account receiving the role.
+    pub fn grant_role(
+        &mut self,
+        role: Role,
+        account_id: AccountId,
+    ) -> bool {
+        self.assert_owner();
+        let mut members = self
+            .roles
+            .get(&role)
+            .unwrap_or_else(|| UnorderedSet::new(StorageKey::RoleMembers { role }));
+        let added = members.insert(&account_id);
+        self.roles.insert(&role, &members);
+        added
+    }
+
+    /// Revokes a role from an account.
+    ///
+    /// Returns `true` if the role was revoked.  
+    /// Returns `false` if the account didn't have the role in the first place.
+    ///
+    /// ### Parameters
+    ///
+    /// - `role`: The role being revoked.
+    /// - `account_id`: The account losing the role.
+    pub fn revoke_role(
+        &mut self,
+        role: Role,
+        account_id: AccountId,
+    ) -> bool {
+        self.assert_owner();
+        let mut members = match self.roles.get(&role) {
+            Some(members) => members,
+            None => return false,
+        };
+        let removed = members.remove(&account_id);
+        self.roles.insert(&role, &members);
+        removed
+    }
+
+    /// Checks if the given account has a role, either because it was granted
+    /// or because it's an owner.
+    ///
+    /// ### Parameters
+    ///
+    /// - `role`: The role being checked.
+    /// - `account_id`: The account being checked.
+    pub fn has_role(
+        &self,
+        role: Role,
+        account_id: AccountId,
+    ) -> bool {
+        self.owners.contains(&account_id)
+            || self
+                .roles
+                .get(&role)
+                .map(|members| members.contains(&account_id))
+                .unwrap_or_default()
+    }
+
+    /// Get a list of the accounts that were granted a role.
+    ///
+    /// The owners are not listed.
+    ///
+    /// ### Parameters
+    ///
+    /// - `role`: The role being queried.
+    /// - `from_index`: How many members to skip.
+    /// - `limit`: How many members to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn get_role_members(
+        &self,
+        role: Role,
+        from_index: Option<near_sdk::json_types::U64>,
+        limit: Option<u16>,
+    ) -> Vec<AccountId> {
+        self.roles
+            .get(&role)
+            .map(|members| {
+                let members = members.as_vector();
+                crate::page_range(from_index, limit, members.len())
+                    .filter_map(|index| members.get(index))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Seats {
+    pub fn assert_role(
+        &self,
+        role: Role,
+    ) {
+        let predecessor = env::predecessor_account_id();
+        require!(
+            self.has_role(role, predecessor.clone()),
+            &format!(
+                "The account {} doesn't have the {:?} role",
+                predecessor, role
+            )
+        );
+    }
+}
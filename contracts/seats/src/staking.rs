@@ -0,0 +1,268 @@
+//! Staking of SEAT tokens, where the per-mint reward of a configured vesting
+//! set is shared by the stakers, pro rata to their stakes, instead of by the
+//! set members.
+//!
+//! Staked tokens leave the staker's balance, and so they don't count as
+//! votes, but they still count in the total supply. The rewards are only
+//! minted when the stakers claim them.
+
+use crate::{fungible_token::events as ft_events, types, Seats};
+use near_sdk::{env, json_types::U128, near_bindgen, require, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Changes which vesting set rewards the stakers, and for how long the
+    /// unstaked tokens are held.
+    ///
+    /// Returns the previous configuration.
+    ///
+    /// ###### Notes
+    ///
+    /// - The members of the rewarding set stop receiving it's rewards.
+    /// - Tokens already being unstaked keep their cooldown.
+    pub fn change_staking_config(
+        &mut self,
+        config: types::StakingConfig,
+    ) -> types::StakingConfig {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        if let Some(reward_set) = &config.reward_set {
+            self.internal_unwrap_vesting_set(reward_set);
+        }
+        std::mem::replace(&mut self.staking.config, config)
+    }
+
+    /// Get which vesting set rewards the stakers, and for how long the
+    /// unstaked tokens are held.
+    pub fn get_staking_config(&self) -> types::StakingConfig {
+        self.staking.config.clone()
+    }
+
+    /// Get how many raw SEAT tokens are staked, not counting the ones being
+    /// unstaked.
+    pub fn get_total_staked(&self) -> U128 {
+        self.staking.total_staked.into()
+    }
+
+    /// Get the stake of an account, with it's rewards settled up to now.
+    pub fn get_stake(
+        &self,
+        account_id: AccountId,
+    ) -> Option<types::Stake> {
+        self.staking.stakes.get(&account_id).map(|mut stake| {
+            self.staking.settle(&mut stake);
+            stake
+        })
+    }
+
+    /// Moves raw SEAT tokens from the caller's balance into it's stake.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// stake, and any extra deposit is refunded.
+    ///
+    /// Returns the caller's stake.
+    #[payable]
+    pub fn stake(
+        &mut self,
+        amount: U128,
+    ) -> types::Stake {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+        require!(amount.0 > 0, "The amount should be a positive number");
+
+        let account_id = env::predecessor_account_id();
+        common::refund_deposit(|| {
+            self.internal_take_balance(&account_id, amount.0);
+
+            let mut stake = self.internal_settled_stake(&account_id);
+            stake.staked.0 += amount.0;
+            self.staking.total_staked += amount.0;
+            self.staking.stakes.insert(&account_id, &stake);
+            stake
+        })
+    }
+
+    /// Unstakes raw SEAT tokens of the caller.
+    ///
+    /// Without a cooldown, the tokens return to the caller's balance right
+    /// away. Otherwise they are held until the cooldown passes, and then must
+    /// be withdrawn by `withdraw_unstaked`.
+    ///
+    /// Returns the caller's stake.
+    ///
+    /// ###### Notes
+    ///
+    /// - Unstaking restarts the cooldown of every token being unstaked.
+    pub fn unstake(
+        &mut self,
+        amount: U128,
+    ) -> types::Stake {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+
+        let account_id = env::predecessor_account_id();
+        let mut stake = self.internal_settled_stake(&account_id);
+        require!(
+            amount.0 <= stake.staked.0,
+            &format!(
+                "cannot unstake {} raw tokens, only {} are staked",
+                amount.0, stake.staked.0
+            )
+        );
+        stake.staked.0 -= amount.0;
+        self.staking.total_staked -= amount.0;
+
+        let cooldown_seconds = self.staking.config.cooldown_seconds;
+        if cooldown_seconds == 0 {
+            self.internal_give_balance(&account_id, amount.0);
+        } else {
+            stake.unstaking.0 += amount.0;
+            stake.unstaking_until = types::Timestamp::from(env::block_timestamp())
+                + types::Timestamp::from_seconds(cooldown_seconds);
+        }
+
+        self.staking.stakes.insert(&account_id, &stake);
+        stake
+    }
+
+    /// Moves the caller's unstaked tokens back into it's balance, after their
+    /// cooldown.
+    ///
+    /// Returns how many raw SEAT tokens got withdrawn.
+    pub fn withdraw_unstaked(&mut self) -> U128 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+
+        let account_id = env::predecessor_account_id();
+        let mut stake = self.internal_settled_stake(&account_id);
+        let now = types::Timestamp::from(env::block_timestamp());
+        require!(
+            now >= stake.unstaking_until,
+            &format!(
+                "the unstaked tokens are held for {} more nanoseconds",
+                stake.unstaking_until.0 - now.0
+            )
+        );
+
+        let amount = std::mem::take(&mut stake.unstaking.0);
+        self.internal_give_balance(&account_id, amount);
+        self.staking.stakes.insert(&account_id, &stake);
+        amount.into()
+    }
+
+    /// Mints the caller's staking rewards into it's balance.
+    ///
+    /// Returns how many raw SEAT tokens got minted.
+    pub fn claim_staking_rewards(&mut self) -> U128 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Claims);
+
+        let account_id = env::predecessor_account_id();
+        let mut stake = self.internal_settled_stake(&account_id);
+        let amount = std::mem::take(&mut stake.rewards.0);
+        self.staking.stakes.insert(&account_id, &stake);
+
+        if amount > 0 {
+            let user = self.internal_unwrap_user(&account_id);
+            self.internal_user_deposit(&account_id, user, amount, None);
+            ft_events::FtMint {
+                owner_id: &account_id,
+                amount: &amount.into(),
+                memo: Some("staking"),
+            }
+            .emit();
+        }
+
+        amount.into()
+    }
+}
+
+impl Seats {
+    /// Shares a reward between the stakers, returning how much was shared.
+    ///
+    /// Without stakers, nothing is shared.
+    pub fn internal_distribute_staking_reward(
+        &mut self,
+        reward: Balance,
+    ) -> Balance {
+        if self.staking.total_staked == 0 {
+            return 0;
+        }
+        let per_share = reward
+            .checked_mul(types::Staking::PRECISION)
+            .unwrap_or_else(|| env::panic_str("Staking reward overflow"))
+            / self.staking.total_staked;
+        self.staking.reward_per_share += per_share;
+        reward
+    }
+
+    /// Removes the stake of an account, returning how many raw SEAT tokens
+    /// were staked or being unstaked.
+    ///
+    /// The unclaimed rewards are lost.
+    pub fn internal_remove_stake(
+        &mut self,
+        account_id: &AccountId,
+    ) -> Balance {
+        match self.staking.stakes.remove(account_id) {
+            Some(stake) => {
+                self.staking.total_staked -= stake.staked.0;
+                stake.staked.0 + stake.unstaking.0
+            }
+            None => 0,
+        }
+    }
+
+    /// The stake of an account, with it's rewards settled up to now.
+    fn internal_settled_stake(
+        &self,
+        account_id: &AccountId,
+    ) -> types::Stake {
+        let mut stake = self
+            .staking
+            .stakes
+            .get(account_id)
+            .unwrap_or_else(|| types::Stake {
+                staked: 0.into(),
+                reward_per_share: self.staking.reward_per_share.into(),
+                rewards: 0.into(),
+                unstaking: 0.into(),
+                unstaking_until: types::Timestamp::default(),
+            });
+        self.staking.settle(&mut stake);
+        stake
+    }
+
+    /// Moves tokens out of the balance, keeping them in the total supply.
+    fn internal_take_balance(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let mut user = self.internal_unwrap_user(account_id);
+        require!(
+            user.balance.0 >= amount,
+            "The account doesn't have enough balance"
+        );
+        user.balance.0 -= amount;
+        self.internal_on_balance_change(account_id, user.balance.0 + amount, user.balance.0);
+        self.accounts.insert(account_id, &user);
+    }
+
+    /// Moves tokens, which are already in the total supply, into the balance.
+    fn internal_give_balance(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let mut user = self.internal_unwrap_user(account_id);
+        user.balance.0 += amount;
+        self.internal_on_balance_change(account_id, user.balance.0 - amount, user.balance.0);
+        self.accounts.insert(account_id, &user);
+    }
+}
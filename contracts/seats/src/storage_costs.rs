@@ -1,3 +1,10 @@
+//! Worst-case storage costs.
+//!
+//! The vesting and registration methods charge only for the storage they
+//! actually use, and refund any extra deposit, so these values are safe
+//! amounts to attach.
+//! Registrations also keep the [`user::HISTORY`] prepaid storage.
+
 use near_sdk::Balance;
 use near_units::parse_near;
 
@@ -9,20 +16,35 @@ pub const CONTRACT: Balance = parse_near!("3 N");
 pub mod user {
     use super::*;
 
-    /// Cost for tracking a token-owning user.
+    /// Cost for tracking a token-owning user, including it's entry in the
+    /// enumerable account index and the balance of it's prepaid history.
     ///
-    /// 1_419_330_452_312_500_000_000 (~1.5 mN)
-    pub const TOKEN_OWNER: Balance = parse_near!("2 mN");
+    /// ~4.5 mN.
+    pub const TOKEN_OWNER: Balance = parse_near!("5 mN");
 
     /// Maximum cost for tracking a vesting user.
     ///
     /// 4_910_000_000_000_000_000_000 (~4.9 mN)
     pub const VESTING: Balance = parse_near!("5 mN");
 
-    /// Cost for registering a user, wort-case scenario.
+    /// Maximum cost for adding a checkpoint to an account's balance or vote
+    /// history, including the history's own entry for the first one.
+    ///
+    /// ~1.9 mN.
+    pub const CHECKPOINT: Balance = parse_near!("2 mN");
+
+    /// Prepaid storage for the growth of an account's balance and vote
+    /// history, which is kept from the registration deposit.
+    ///
+    /// Covers at least 10 checkpoints, after which the history stops
+    /// growing.
+    pub const HISTORY: Balance = 10 * CHECKPOINT;
+
+    /// Cost for registering a user, wort-case scenario, including the
+    /// prepaid history.
     ///
-    /// ~2 mN.
-    pub const BALANCE_REQUIREMENT: Balance = TOKEN_OWNER;
+    /// ~25 mN.
+    pub const BALANCE_REQUIREMENT: Balance = TOKEN_OWNER + HISTORY;
 }
 
 /// Cost for registering a new user set, worst-case scenario.
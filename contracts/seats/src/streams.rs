@@ -0,0 +1,293 @@
+//! Streams of SEAT tokens, where a sender pays a receiver continuously, at a
+//! constant rate per second.
+//!
+//! The streamed tokens are taken from the sender's balance when the stream is
+//! created, and are held by the stream until they are withdrawn by the
+//! receiver, or until the stream is canceled. While held, they still count in
+//! the total supply, but the events report them as burned from the sender and
+//! then minted to whom they are released, so that the balances rebuilt from
+//! the events stay correct.
+
+use crate::{
+    events,
+    fungible_token::events as ft_events,
+    types::{self, Timestamp},
+    Seats,
+};
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    near_bindgen, require, AccountId, Balance,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Creates a stream from the caller to a receiver, taking every token to
+    /// be streamed from the caller's balance.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// stream, and any extra deposit is refunded.
+    ///
+    /// Returns the stream id.
+    ///
+    /// ### Parameters
+    ///
+    /// - `receiver_id`: The registered account that receives the tokens.
+    /// - `rate_per_second`: How many raw SEAT tokens are streamed each second.
+    /// - `start`: When the stream starts, which cannot be in the past.
+    /// - `stop`: When the stream stops, which must be after it starts.
+    #[payable]
+    pub fn create_stream(
+        &mut self,
+        receiver_id: AccountId,
+        rate_per_second: U128,
+        start: Timestamp,
+        stop: Timestamp,
+    ) -> U64 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+
+        let sender_id = env::predecessor_account_id();
+        require!(
+            sender_id != receiver_id,
+            "Sender and receiver should be different"
+        );
+        self.internal_assert_registered(&receiver_id);
+        require!(
+            rate_per_second.0 > 0,
+            "The rate should be a positive number"
+        );
+        let now = Timestamp::from(env::block_timestamp());
+        require!(start >= now, "the stream cannot start in the past");
+        require!(stop > start, "the stream must stop after it starts");
+
+        let deposit = rate_per_second
+            .0
+            .checked_mul((stop - start).0 as u128)
+            .unwrap_or_else(|| env::panic_str("Stream deposit overflow"))
+            / Timestamp::SECS_TO_NANO as u128;
+        require!(deposit > 0, "the stream has no tokens to stream");
+
+        self.internal_withdraw(&sender_id, deposit);
+        // the streamed tokens stay in the total supply
+        self.total_supply += deposit;
+        self.internal_checkpoint_supply();
+        ft_events::FtBurn {
+            owner_id: &sender_id,
+            amount: &deposit.into(),
+            memo: Some("stream"),
+        }
+        .emit();
+
+        let stream = types::Stream {
+            sender_id,
+            receiver_id,
+            rate_per_second,
+            start,
+            stop,
+            deposit: deposit.into(),
+            withdrawn: 0.into(),
+            canceled_at: None,
+        };
+        let stream_id = self.streams.len();
+        common::refund_deposit(|| self.streams.push(&stream));
+
+        events::StreamCreate {
+            stream_id: stream_id.into(),
+            stream: &stream,
+        }
+        .emit();
+
+        stream_id.into()
+    }
+
+    /// Moves the tokens streamed so far, and not yet withdrawn, into the
+    /// receiver's balance.
+    ///
+    /// Can only be called by the receiver.
+    ///
+    /// Returns how many raw SEAT tokens got withdrawn.
+    pub fn withdraw_from_stream(
+        &mut self,
+        stream_id: U64,
+    ) -> U128 {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+
+        let mut stream = self.internal_unwrap_stream(stream_id.0);
+        let receiver_id = env::predecessor_account_id();
+        require!(
+            stream.receiver_id == receiver_id,
+            &format!(
+                "account {} is not the receiver of stream {}",
+                receiver_id, stream_id.0
+            )
+        );
+
+        let now = Timestamp::from(env::block_timestamp());
+        let amount = stream.withdrawable(now);
+        require!(
+            amount > 0,
+            &format!("stream {} has no tokens to withdraw", stream_id.0)
+        );
+        stream.withdrawn.0 += amount;
+        self.streams.replace(stream_id.0, &stream);
+        self.internal_release_stream(&receiver_id, amount);
+
+        events::StreamWithdraw {
+            stream_id,
+            receiver_id: &receiver_id,
+            amount: amount.into(),
+        }
+        .emit();
+
+        amount.into()
+    }
+
+    /// Cancels a stream that has not yet stopped. The tokens streamed so far
+    /// and not yet withdrawn go to the receiver, and the remaining ones
+    /// return to the sender.
+    ///
+    /// Can be called by either the sender or the receiver.
+    ///
+    /// ###### Notes
+    ///
+    /// - If the sender or the receiver are no longer registered, their part
+    /// is burned.
+    pub fn cancel_stream(
+        &mut self,
+        stream_id: U64,
+    ) -> types::StreamInfo {
+        self.assert_non_migration();
+        self.assert_not_paused(types::Feature::Transfers);
+
+        let mut stream = self.internal_unwrap_stream(stream_id.0);
+        let account_id = env::predecessor_account_id();
+        require!(
+            account_id == stream.sender_id || account_id == stream.receiver_id,
+            &format!(
+                "account {} is neither the sender nor the receiver of stream {}",
+                account_id, stream_id.0
+            )
+        );
+
+        let now = Timestamp::from(env::block_timestamp());
+        match stream.status(now) {
+            types::StreamStatus::Pending | types::StreamStatus::Streaming => {}
+            status => env::panic_str(&format!(
+                "stream {} cannot be canceled, it's status is {:?}",
+                stream_id.0, status
+            )),
+        }
+
+        let streamed = stream.streamed(now);
+        let receiver_amount = streamed - stream.withdrawn.0;
+        let sender_amount = stream.deposit.0 - streamed;
+        stream.withdrawn = streamed.into();
+        stream.canceled_at = Some(now);
+        self.streams.replace(stream_id.0, &stream);
+
+        self.internal_release_stream(&stream.receiver_id, receiver_amount);
+        self.internal_release_stream(&stream.sender_id, sender_amount);
+
+        events::StreamCancel {
+            stream_id,
+            account_id: &account_id,
+            sender_amount: sender_amount.into(),
+            receiver_amount: receiver_amount.into(),
+        }
+        .emit();
+
+        types::StreamInfo {
+            id: stream_id,
+            status: stream.status(now),
+            withdrawable: 0.into(),
+            stream,
+        }
+    }
+
+    /// Get a stream, with it's current status.
+    pub fn get_stream(
+        &self,
+        stream_id: U64,
+    ) -> Option<types::StreamInfo> {
+        let now = Timestamp::from(env::block_timestamp());
+        self.streams
+            .get(stream_id.0)
+            .map(|stream| stream_info(stream_id.0, stream, now))
+    }
+
+    /// Get the streams, with their current status.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many streams to skip.
+    /// - `limit`: How many streams to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
+    pub fn get_streams(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u16>,
+    ) -> Vec<types::StreamInfo> {
+        let now = Timestamp::from(env::block_timestamp());
+
+        crate::page_range(from_index, limit, self.streams.len())
+            .filter_map(|id| Some(stream_info(id, self.streams.get(id)?, now)))
+            .collect()
+    }
+}
+
+impl Seats {
+    pub fn internal_unwrap_stream(
+        &self,
+        stream_id: u64,
+    ) -> types::Stream {
+        self.streams
+            .get(stream_id)
+            .unwrap_or_else(|| env::panic_str(&format!("missing stream {}", stream_id)))
+    }
+
+    /// Moves tokens held by a stream into an account's balance, or burns them
+    /// if the account is no longer registered.
+    fn internal_release_stream(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        if amount == 0 {
+            return;
+        }
+
+        // the tokens were already in the total supply
+        self.total_supply -= amount;
+        match self.accounts.get(account_id) {
+            Some(user) => {
+                self.internal_user_deposit(account_id, user, amount, None);
+                ft_events::FtMint {
+                    owner_id: account_id,
+                    amount: &amount.into(),
+                    memo: Some("stream"),
+                }
+                .emit();
+            }
+            // the burn was already reported when the stream got created
+            None => self.internal_checkpoint_supply(),
+        }
+    }
+}
+
+fn stream_info(
+    id: u64,
+    stream: types::Stream,
+    now: Timestamp,
+) -> types::StreamInfo {
+    types::StreamInfo {
+        id: id.into(),
+        status: stream.status(now),
+        withdrawable: stream.withdrawable(now).into(),
+        stream,
+    }
+}
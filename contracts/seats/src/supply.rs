@@ -0,0 +1,93 @@
+//! Views that break down the total supply, such as for aggregators that
+//! report the circulating supply.
+
+use crate::{types, Seats};
+use near_sdk::{env, json_types::U128, near_bindgen};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Get the tokens that are neither waiting to be claimed nor held by the
+    /// treasury.
+    pub fn ft_circulating_supply(&self) -> U128 {
+        self.get_supply_breakdown().circulating_supply
+    }
+
+    /// Get how the total supply is split.
+    pub fn get_supply_breakdown(&self) -> types::SupplyBreakdown {
+        let now = types::Timestamp::from(env::block_timestamp());
+        let treasury = self.internal_treasury_balance();
+        let circulating = self
+            .total_supply
+            .saturating_sub(self.supply.claim_balance)
+            .saturating_sub(treasury);
+
+        types::SupplyBreakdown {
+            total_supply: self.total_supply.into(),
+            circulating_supply: circulating.into(),
+            claim_balance: self.supply.claim_balance.into(),
+            vesting_locked: self.supply.locked(now).into(),
+            treasury: treasury.into(),
+        }
+    }
+
+    /// Returns the total supply as plain text, in whole SEAT tokens, such as
+    /// `1234.50000`.
+    ///
+    /// ###### Notes
+    ///
+    /// - The value is returned as-is, without being serialized into json.
+    pub fn get_total_supply_text(&self) {
+        self.internal_return_text(self.total_supply);
+    }
+
+    /// Returns the circulating supply as plain text, in whole SEAT tokens,
+    /// such as `1234.50000`.
+    ///
+    /// ###### Notes
+    ///
+    /// - The value is returned as-is, without being serialized into json.
+    pub fn get_circulating_supply_text(&self) {
+        self.internal_return_text(self.ft_circulating_supply().0);
+    }
+}
+
+impl Seats {
+    /// The tokens in the treasury's balance, if it's configured.
+    pub fn internal_treasury_balance(&self) -> u128 {
+        self.treasury
+            .as_ref()
+            .and_then(|treasury_id| self.accounts.get(treasury_id))
+            .map(|treasury| treasury.balance.0)
+            .unwrap_or_default()
+    }
+
+    /// Returns an amount of raw tokens in whole tokens, using the decimals
+    /// from the metadata.
+    fn internal_return_text(
+        &self,
+        amount: u128,
+    ) {
+        let decimals = self.metadata.get().unwrap().decimals;
+        env::value_return(format_amount(amount, decimals).as_bytes());
+    }
+}
+
+/// Formats an amount of raw tokens into whole tokens.
+pub fn format_amount(
+    amount: u128,
+    decimals: u8,
+) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let padding = u128::pow(10, decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / padding,
+        amount % padding,
+        width = decimals as usize
+    )
+}
@@ -1,6 +1,6 @@
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::UnorderedMap,
+    collections::{LookupMap, TreeMap, UnorderedMap, Vector},
     json_types::{U128, U64},
     serde::{Deserialize, Serialize},
     AccountId,
@@ -14,6 +14,22 @@ pub struct User {
     pub balance: U128,
     /// The amount of SEAT tokens that the user can claim.
     pub claim_balance: U128,
+    /// The tokens that the user can claim, by where they came from.
+    ///
+    /// Their sum may be below `claim_balance`, as tokens that were deposited
+    /// before the sources got tracked are not in any bucket.
+    ///
+    /// The tokens from a source share a bucket while they unlock and expire
+    /// together.
+    /// Each set membership and receiver keeps one bucket while empty, so
+    /// that minting doesn't grow the account's storage.
+    pub claims: Vec<ClaimBucket>,
+    /// How many yoctoNEAR the account deposited for it's registration, which
+    /// is the most that gets refunded when it unregisters.
+    ///
+    /// Accounts registered by the owner, or before the deposits got tracked,
+    /// have nothing to be refunded.
+    pub storage_deposit: U128,
 }
 
 impl Default for User {
@@ -21,10 +37,241 @@ impl Default for User {
         Self {
             balance: 0.into(),
             claim_balance: 0.into(),
+            claims: vec![],
+            storage_deposit: 0.into(),
         }
     }
 }
 
+impl User {
+    /// Adds claimable tokens into the bucket of their source that unlocks and
+    /// expires together with them, or else into an empty bucket of their
+    /// source, or else into a new bucket.
+    ///
+    /// The tokens already in the buckets keep their unlock dates.
+    ///
+    /// The `claim_balance` is not changed.
+    pub fn add_claim(
+        &mut self,
+        terms: ClaimTerms,
+        updated_at: Timestamp,
+        amount: u128,
+    ) {
+        let index = self
+            .claims
+            .iter()
+            .position(|bucket| {
+                bucket.source == terms.source
+                    && bucket.unlock_at == terms.unlock_at
+                    && bucket.expires_at == terms.expires_at
+            })
+            .or_else(|| {
+                self.claims
+                    .iter()
+                    .position(|bucket| bucket.source == terms.source && bucket.amount.0 == 0)
+            });
+        match index {
+            Some(index) => {
+                let bucket = &mut self.claims[index];
+                bucket.unlock_at = terms.unlock_at;
+                bucket.expires_at = terms.expires_at;
+                bucket.updated_at = updated_at;
+                bucket.amount.0 += amount;
+            }
+            None => self.claims.push(ClaimBucket {
+                source: terms.source,
+                updated_at,
+                unlock_at: terms.unlock_at,
+                expires_at: terms.expires_at,
+                amount: amount.into(),
+            }),
+        }
+    }
+
+    /// Adds an empty bucket for the source, if there is none yet.
+    ///
+    /// Returns whether it got added.
+    pub fn reserve_claim(
+        &mut self,
+        source: ClaimSource,
+    ) -> bool {
+        if self.claims.iter().any(|bucket| bucket.source == source) {
+            return false;
+        }
+        self.claims.push(ClaimBucket {
+            source,
+            updated_at: Timestamp::default(),
+            unlock_at: Timestamp::default(),
+            expires_at: None,
+            amount: 0.into(),
+        });
+        true
+    }
+
+    /// The tokens that are not in any bucket, such as the ones deposited
+    /// before the sources got tracked.
+    pub fn untracked_claims(&self) -> u128 {
+        let tracked: u128 = self.claims.iter().map(|bucket| bucket.amount.0).sum();
+        self.claim_balance.0.saturating_sub(tracked)
+    }
+
+    /// How many tokens can be claimed at the given time.
+    ///
+    /// Tokens that are not in any bucket are always unlocked.
+    pub fn claimable(
+        &self,
+        now: Timestamp,
+    ) -> u128 {
+        let unlocked: u128 = self
+            .claims
+            .iter()
+            .filter(|bucket| bucket.unlock_at <= now)
+            .map(|bucket| bucket.amount.0)
+            .sum();
+        self.untracked_claims() + unlocked
+    }
+
+    /// Releases unlocked claimable tokens from the buckets, starting with the
+    /// tokens that are not in any bucket and then from the buckets that
+    /// unlocked first.
+    ///
+    /// The emptied buckets are kept.
+    ///
+    /// The `claim_balance` is not changed, and must be reduced separately.
+    pub fn release_claims(
+        &mut self,
+        mut amount: u128,
+        now: Timestamp,
+    ) {
+        amount = amount.saturating_sub(self.untracked_claims());
+
+        let mut unlocked: Vec<&mut ClaimBucket> = self
+            .claims
+            .iter_mut()
+            .filter(|bucket| bucket.unlock_at <= now)
+            .collect();
+        unlocked.sort_by_key(|bucket| bucket.unlock_at);
+        for bucket in unlocked {
+            let released = std::cmp::min(amount, bucket.amount.0);
+            bucket.amount.0 -= released;
+            amount -= released;
+        }
+    }
+
+    /// Whether there are tokens from a set that were earned by the member.
+    pub fn has_set_claims(
+        &self,
+        set: &SetName,
+        member_id: &AccountId,
+    ) -> bool {
+        self.claims
+            .iter()
+            .any(|bucket| bucket.is_from(set, member_id) && bucket.amount.0 > 0)
+    }
+
+    /// Removes and returns the buckets from a set that were earned by the
+    /// member.
+    ///
+    /// The `claim_balance` is not changed.
+    pub fn take_set_claims(
+        &mut self,
+        set: &SetName,
+        member_id: &AccountId,
+    ) -> Vec<ClaimBucket> {
+        let mut taken = vec![];
+        self.claims.retain(|bucket| {
+            if bucket.is_from(set, member_id) {
+                taken.push(bucket.clone());
+                false
+            } else {
+                true
+            }
+        });
+        taken
+    }
+}
+
+/// Claimable tokens that came from the same source.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimBucket {
+    pub source: ClaimSource,
+    /// When the last tokens were added into this bucket.
+    pub updated_at: Timestamp,
+    /// Before when the tokens can't be claimed.
+    pub unlock_at: Timestamp,
+    /// When the tokens are forfeited, by the claim window they were rewarded
+    /// with, if they expire at all.
+    pub expires_at: Option<Timestamp>,
+    pub amount: U128,
+}
+
+impl ClaimBucket {
+    /// Whether the tokens came from a set, earned by the member.
+    pub fn is_from(
+        &self,
+        set: &SetName,
+        member_id: &AccountId,
+    ) -> bool {
+        matches!(
+            &self.source,
+            ClaimSource::Set { name, member } if name == set && member == member_id
+        )
+    }
+}
+
+/// Where some claimable tokens come from, and when they can be claimed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClaimTerms {
+    pub source: ClaimSource,
+    pub unlock_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Where some claimable tokens came from.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "t", content = "c")]
+pub enum ClaimSource {
+    /// Minted by `force_mint`.
+    ForceMint,
+    /// Rewarded by a vesting set, on any of it's generations.
+    ///
+    /// The `member` is the one whose position earned the tokens, which is
+    /// not the bucket's account if the member named a reward receiver.
+    Set { name: SetName, member: AccountId },
+}
+
+/// For how long the rewards of a vesting set can be claimed, after which they
+/// are forfeited.
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimWindow {
+    /// For how many seconds, after being rewarded, the tokens can be claimed.
+    ///
+    /// Tokens that are rewarded while locked can be claimed for this long
+    /// after they unlock.
+    pub seconds: u32,
+    /// Whether the forfeited tokens are burned. Otherwise they are given to
+    /// the treasury, or burned if there's no treasury.
+    pub burn: bool,
+}
+
+impl ClaimWindow {
+    /// When the tokens rewarded at `rewarded_at`, which unlock at
+    /// `unlock_at`, expire.
+    pub fn expires_at(
+        &self,
+        rewarded_at: Timestamp,
+        unlock_at: Timestamp,
+    ) -> Timestamp {
+        Timestamp::from(std::cmp::max(rewarded_at.0, unlock_at.0))
+            + Timestamp::from_seconds(self.seconds)
+    }
+}
+
 /// Specifies an amount of raw SEAT tokens.
 ///
 /// 1 SEAT = 100_000 raw tokens.
@@ -68,7 +315,7 @@ pub struct RewardPercentage(
 );
 
 impl RewardPercentage {
-    const MAX: Self = Self(100_000_000_000);
+    pub const MAX: Self = Self(100_000_000_000);
 
     pub fn new(percentage: u64) -> Self {
         let this = Self(percentage);
@@ -108,7 +355,9 @@ impl From<u64> for RewardPercentage {
     Clone,
     Copy,
     PartialEq,
+    Eq,
     PartialOrd,
+    Ord,
     Debug,
     Default,
 )]
@@ -149,7 +398,7 @@ impl std::ops::Sub for Timestamp {
 }
 
 impl Timestamp {
-    const SECS_TO_NANO: u64 = 1_000_000_000;
+    pub const SECS_TO_NANO: u64 = 1_000_000_000;
 
     pub fn from_seconds(seconds: u32) -> Self {
         Self(seconds as u64 * Self::SECS_TO_NANO)
@@ -198,12 +447,235 @@ pub struct MintInfo {
     pub vesting: Vesting,
     /// The state in which the minting operation is at.
     pub state: MintState,
+    /// Limits for the `force_mint` operations.
+    pub budget: MintBudget,
+}
+
+/// Limits on how many raw SEAT tokens can be force-minted by owners within a
+/// window of time.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MintBudget {
+    /// For how many seconds a window lasts, after which the budget usage gets
+    /// reset.
+    pub window_seconds: u32,
+    /// How many raw SEAT tokens all owners together can force-mint within a
+    /// window.
+    ///
+    /// If `None`, there is no global limit.
+    pub global_limit: Option<RewardAmount>,
+    /// How many raw SEAT tokens each owner can force-mint within a window.
+    ///
+    /// If `None`, there is no per-owner limit.
+    pub owner_limit: Option<RewardAmount>,
+    /// Budget usage from all owners together.
+    pub global_usage: MintBudgetUsage,
+    /// Budget usage from each owner.
+    pub owner_usage: LookupMap<AccountId, MintBudgetUsage>,
+    /// A change that raises the limits, waiting for it's timelock.
+    pub pending: Option<PendingMintBudget>,
+}
+
+impl MintBudget {
+    /// For how long a change that raises the limits waits before it applies,
+    /// so the other owners can cancel it.
+    pub const RAISE_DELAY_SECONDS: u32 = 2 * crate::constant_init::DAY;
+
+    pub fn config(&self) -> MintBudgetConfig {
+        MintBudgetConfig {
+            window_seconds: self.window_seconds,
+            global_limit: self.global_limit,
+            owner_limit: self.owner_limit,
+        }
+    }
+
+    pub fn set_config(
+        &mut self,
+        config: MintBudgetConfig,
+    ) {
+        self.window_seconds = config.window_seconds;
+        self.global_limit = config.global_limit;
+        self.owner_limit = config.owner_limit;
+    }
+
+    /// Applies the pending change if it's timelock has passed.
+    pub fn apply_pending(
+        &mut self,
+        now: Timestamp,
+    ) {
+        if let Some(pending) = self.pending {
+            if now >= pending.applies_at {
+                self.set_config(pending.config);
+                self.pending = None;
+            }
+        }
+    }
+}
+
+/// A mint budget change that raises the limits, which only applies after a
+/// timelock.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingMintBudget {
+    pub config: MintBudgetConfig,
+    /// When the change applies.
+    pub applies_at: Timestamp,
+}
+
+/// How much of a mint budget has been used within a window.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintBudgetUsage {
+    /// When the current window has started.
+    pub window_start: Timestamp,
+    /// How many raw SEAT tokens were force-minted since `window_start`.
+    pub used: RewardAmount,
+}
+
+impl MintBudgetUsage {
+    /// The timestamp in which the current window ends.
+    pub fn window_end(
+        &self,
+        window: Timestamp,
+    ) -> Timestamp {
+        self.window_start + window
+    }
+
+    /// Starts a new window if the current one has already ended.
+    pub fn refresh(
+        &mut self,
+        now: Timestamp,
+        window: Timestamp,
+    ) {
+        if now >= self.window_end(window) {
+            *self = Self {
+                window_start: now,
+                used: RewardAmount::default(),
+            };
+        }
+    }
+
+    /// Uses `amount` from the budget, panicking if that would go above the
+    /// `limit`.
+    pub fn consume(
+        &mut self,
+        amount: u128,
+        limit: Option<RewardAmount>,
+        now: Timestamp,
+        window: Timestamp,
+        budget_name: &str,
+    ) {
+        self.refresh(now, window);
+        let used = self
+            .used
+            .0
+            .checked_add(amount)
+            .unwrap_or_else(|| near_sdk::env::panic_str("Mint budget overflow"));
+        if let Some(limit) = limit {
+            near_sdk::require!(
+                used <= limit.0,
+                &format!(
+                    "{} mint budget exceeded, {} raw tokens remaining until {} nanoseconds",
+                    budget_name,
+                    limit.0.saturating_sub(self.used.0),
+                    self.window_end(window).0
+                )
+            );
+        }
+        self.used.0 = used;
+    }
+
+    /// Gets the status of the budget at `now`.
+    pub fn status(
+        &self,
+        limit: Option<RewardAmount>,
+        now: Timestamp,
+        window: Timestamp,
+    ) -> MintBudgetStatus {
+        if now >= self.window_end(window) {
+            MintBudgetStatus {
+                remaining: limit,
+                reset_timestamp: None,
+            }
+        } else {
+            MintBudgetStatus {
+                remaining: limit.map(|limit| RewardAmount(limit.0.saturating_sub(self.used.0))),
+                reset_timestamp: Some(self.window_end(window)),
+            }
+        }
+    }
+}
+
+/// The current status of a mint budget.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintBudgetStatus {
+    /// How many raw SEAT tokens can still be force-minted in the current
+    /// window.
+    ///
+    /// If `None`, there is no limit.
+    pub remaining: Option<RewardAmount>,
+    /// When the current window ends, and the budget gets reset.
+    ///
+    /// If `None`, there is no window in progress and the full budget is
+    /// available.
+    pub reset_timestamp: Option<Timestamp>,
+}
+
+/// The mint budget configuration.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintBudgetConfig {
+    /// For how many seconds a window lasts.
+    pub window_seconds: u32,
+    /// How many raw SEAT tokens all owners together can force-mint within a
+    /// window.
+    pub global_limit: Option<RewardAmount>,
+    /// How many raw SEAT tokens each owner can force-mint within a window.
+    pub owner_limit: Option<RewardAmount>,
+}
+
+impl MintBudgetConfig {
+    /// Whether this configuration allows no more force-minting than `other`.
+    ///
+    /// A missing limit is unlimited, and a shorter window only allows more
+    /// force-minting against an existing limit.
+    pub fn is_within(
+        &self,
+        other: &Self,
+    ) -> bool {
+        let shorter_window = self.window_seconds < other.window_seconds;
+        let limit_within =
+            |limit: Option<RewardAmount>, other: Option<RewardAmount>| match (limit, other) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some(limit), Some(other)) => limit.0 <= other.0 && !shorter_window,
+            };
+
+        limit_within(self.global_limit, other.global_limit)
+            && limit_within(self.owner_limit, other.owner_limit)
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Vesting {
     /// Sets and their Accounts that receive for vesting.
     pub sets: UnorderedMap<SetName, VestingUserSet>,
+    /// Accounts that receive the rewards of a member from a set, instead of
+    /// the member itself.
+    ///
+    /// ([`SetName`], member) -> receiver.
+    pub receivers: LookupMap<(SetName, AccountId), AccountId>,
+    /// Changes requested by members over their own position in a set, that
+    /// are waiting for an owner approval.
+    ///
+    /// ([`SetName`], member) -> [`VestingRequest`].
+    pub requests: LookupMap<(SetName, AccountId), VestingRequest>,
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -236,6 +708,89 @@ pub struct VestingUserSetInfo {
     /// Whether users must still claim their token rewards,
     /// or they get directly rewarded of their tokens.
     pub users_must_claim: bool,
+    /// Whether this set is being removed, in which case it's members are
+    /// being removed in pages and it no longer mints rewards.
+    pub removing: bool,
+    /// Whether this set is sealed, in which case it's reward, dates and
+    /// members can no longer be changed.
+    pub sealed: bool,
+    /// Whether the changes requested by members over their own position, such
+    /// as naming a reward receiver, must be approved by an owner.
+    pub requests_need_approval: bool,
+    /// Whether members can be revoked from this set, in which case their
+    /// percentage goes to the treasury.
+    pub revocable: bool,
+    /// For how long the rewards of this set can be claimed, if they must be
+    /// claimed at all.
+    pub claim_window: Option<ClaimWindow>,
+    /// Before when the rewards of this set can't be claimed, if they must be
+    /// claimed at all.
+    ///
+    /// Rewards keep the unlock date that the set had when they were given.
+    pub claim_unlock_date: Timestamp,
+}
+
+impl VestingUserSet {
+    /// Panics if the set is being removed, as it can no longer be changed.
+    pub fn assert_not_removing(
+        &self,
+        name: &SetName,
+    ) {
+        near_sdk::require!(
+            !self.info.removing,
+            &format!("set {} is being removed", name.0)
+        );
+    }
+
+    /// Panics if the set is sealed, as it can no longer be changed.
+    pub fn assert_not_sealed(
+        &self,
+        name: &SetName,
+    ) {
+        near_sdk::require!(!self.info.sealed, &format!("set {} is sealed", name.0));
+    }
+}
+
+/// A change requested by a member over it's own position in a vesting set.
+#[derive(Clone, PartialEq, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "t", content = "c")]
+pub enum VestingRequest {
+    /// The member's rewards from the set should be deposited into another
+    /// account, or back into the member if `None`.
+    Receiver { receiver_id: Option<AccountId> },
+    /// The member's position on the set should be transferred into another
+    /// account.
+    Transfer { new_account_id: AccountId },
+}
+
+/// Information about a member revoked from a vesting set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Revocation {
+    /// The member's percentage, which was given to the treasury.
+    pub percentage: RewardPercentage,
+    /// How many raw SEAT tokens, not yet claimed, were taken from the member
+    /// and given to the treasury.
+    pub clawed_back: U128,
+}
+
+/// Information about a removed, or being removed, vesting set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemovedVestingSet {
+    /// The set information, before it's removal.
+    pub info: VestingUserSetInfo,
+    /// How many members were removed by this call.
+    pub removed_members: u64,
+    /// How many members are still to be removed.
+    ///
+    /// If above zero, the set is still being removed and
+    /// `step_remove_vesting_set` must be called again.
+    pub remaining_members: u64,
+    /// How many yoctoNEAR were refunded to the caller, for the storage freed
+    /// by the set and it's members on this call.
+    pub refunded: U128,
 }
 
 #[derive(Clone, PartialEq, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
@@ -254,3 +809,660 @@ pub enum MintState {
         user_offset: u64,
     },
 }
+
+/// Information about state migrations.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MigrationInfo {
+    /// The state version from which the contract was last migrated.
+    pub from_version: u16,
+    /// The state in which the migration of the vesting members is at.
+    pub state: MigrationState,
+    /// How many accounts were converted by `migrate_accounts`.
+    pub converted_accounts: u64,
+    /// How many raw SEAT tokens, either in balances or claim balances, are
+    /// still in accounts that were not converted.
+    pub unconverted_supply: u128,
+}
+
+/// The progress on the conversion of the accounts.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountsMigration {
+    pub converted_accounts: U64,
+    pub unconverted_supply: U128,
+}
+
+#[derive(Clone, PartialEq, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "t", content = "c")]
+pub enum MigrationState {
+    /// No migration procedure is happening.
+    Standby,
+    /// Importing the state exported from another contract.
+    Import {
+        /// The total supply of the exporting contract, which the imported
+        /// accounts must match.
+        expected_total_supply: U128,
+
+        /// The total of tokens from the accounts and streams imported so far,
+        /// including the staked ones.
+        imported_total_supply: U128,
+    },
+    /// Importing the initial vesting sets and members, after a staged
+    /// initialization.
+    Setup,
+}
+
+/// The mint information exported from, or imported into, a contract.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedMintInfo {
+    /// The timestmap after which the `start_mint` function will be enabled.
+    pub start_timestamp: Timestamp,
+    /// Counter of how many minting procedures has finished.
+    pub generation: u32,
+    /// Number of seconds that the next start_mint procedure should wait
+    /// after the last start_mint operation.
+    pub lock_duration_seconds: u32,
+    /// The last time the minting procedure has started.
+    pub last_mint_timestamp: Option<Timestamp>,
+    /// Limits for the `force_mint` operations.
+    pub budget: MintBudgetConfig,
+    /// Total supply of all of the SEAT tokens.
+    pub total_supply: U128,
+    /// The account that receives revoked and forfeited shares.
+    pub treasury: Option<AccountId>,
+    /// The governance configuration.
+    pub governance: Option<GovernanceConfig>,
+    /// The staking configuration and totals.
+    pub staking: ExportedStaking,
+}
+
+/// The staking state exported from, or imported into, a contract, besides
+/// the stakes of each account.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedStaking {
+    pub config: StakingConfig,
+    /// As in [`Staking::total_staked`].
+    pub total_staked: U128,
+    /// As in [`Staking::reward_per_share`].
+    pub reward_per_share: U128,
+}
+
+/// An account exported from, or imported into, a contract.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedAccount {
+    pub account_id: AccountId,
+    pub user: User,
+    /// To whom the account delegates it's votes.
+    pub delegate_id: Option<AccountId>,
+    /// The account's stake, as last settled.
+    pub stake: Option<Stake>,
+}
+
+/// A vesting member exported from, or imported into, a contract.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedVestingMember {
+    pub account_id: AccountId,
+    pub percentage: RewardPercentage,
+    /// The account that receives the member's rewards, if not the member.
+    pub receiver_id: Option<AccountId>,
+    /// The member's pending request.
+    pub request: Option<VestingRequest>,
+}
+
+/// A previous version of the token metadata.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataVersion {
+    /// The metadata that got replaced.
+    pub metadata: near_contract_standards::fungible_token::metadata::FungibleTokenMetadata,
+    /// When it got replaced.
+    pub replaced_at: Timestamp,
+    /// The account that replaced it.
+    pub replaced_by: AccountId,
+}
+
+/// Roles that can be granted, by the owners, to other accounts.
+///
+/// The owners implicitly have every role.
+#[derive(
+    Clone, Copy, PartialEq, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    /// Can upgrade the contract's code.
+    Migration,
+    /// Can pause and unpause features.
+    Guardian,
+    /// Can update the token metadata.
+    Metadata,
+    /// Can claim tokens on behalf of other accounts.
+    Keeper,
+}
+
+/// Features that can be paused independently.
+#[derive(
+    Clone, Copy, PartialEq, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Feature {
+    /// `ft_transfer` and `ft_transfer_call`.
+    Transfers,
+    /// `force_mint`, `start_mint` and `step_mint`.
+    Minting,
+    /// `claim`.
+    Claims,
+    /// `storage_deposit` and `storage_unregister`.
+    Registrations,
+}
+
+/// Which features are paused.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseFlags {
+    pub transfers: bool,
+    pub minting: bool,
+    pub claims: bool,
+    pub registrations: bool,
+}
+
+impl PauseFlags {
+    pub fn is_paused(
+        &self,
+        feature: Feature,
+    ) -> bool {
+        match feature {
+            Feature::Transfers => self.transfers,
+            Feature::Minting => self.minting,
+            Feature::Claims => self.claims,
+            Feature::Registrations => self.registrations,
+        }
+    }
+
+    pub fn set(
+        &mut self,
+        feature: Feature,
+        paused: bool,
+    ) {
+        let flag = match feature {
+            Feature::Transfers => &mut self.transfers,
+            Feature::Minting => &mut self.minting,
+            Feature::Claims => &mut self.claims,
+            Feature::Registrations => &mut self.registrations,
+        };
+        *flag = paused;
+    }
+}
+
+/// Aggregate counters over every account, so the supply can be broken down
+/// without enumerating the accounts.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SupplyCounters {
+    /// Tokens in the claim balance of every account.
+    pub claim_balance: u128,
+    /// Tokens in claim buckets that were still locked when counted, by their
+    /// unlock date.
+    ///
+    /// Entries whose unlock date has passed are no longer counted, and are
+    /// pruned on the next change.
+    pub locked_claims: TreeMap<Timestamp, u128>,
+}
+
+impl SupplyCounters {
+    /// Counts claimable tokens that unlock at the given date.
+    pub fn add_claim(
+        &mut self,
+        unlock_at: Timestamp,
+        amount: u128,
+        now: Timestamp,
+    ) {
+        self.prune(now);
+        self.claim_balance += amount;
+        if unlock_at > now && amount > 0 {
+            let locked = self.locked_claims.get(&unlock_at).unwrap_or_default();
+            self.locked_claims.insert(&unlock_at, &(locked + amount));
+        }
+    }
+
+    /// Stops counting claimable tokens that unlock at the given date.
+    pub fn remove_claim(
+        &mut self,
+        unlock_at: Timestamp,
+        amount: u128,
+        now: Timestamp,
+    ) {
+        self.prune(now);
+        self.claim_balance -= amount;
+        if unlock_at > now {
+            if let Some(locked) = self.locked_claims.get(&unlock_at) {
+                let locked = locked.saturating_sub(amount);
+                if locked == 0 {
+                    self.locked_claims.remove(&unlock_at);
+                } else {
+                    self.locked_claims.insert(&unlock_at, &locked);
+                }
+            }
+        }
+    }
+
+    /// Counts every claimable token of a user.
+    pub fn add_user(
+        &mut self,
+        user: &User,
+        now: Timestamp,
+    ) {
+        self.add_claim(Timestamp::default(), user.untracked_claims(), now);
+        for bucket in &user.claims {
+            self.add_claim(bucket.unlock_at, bucket.amount.0, now);
+        }
+    }
+
+    /// Stops counting every claimable token of a user.
+    pub fn remove_user(
+        &mut self,
+        user: &User,
+        now: Timestamp,
+    ) {
+        self.remove_claim(Timestamp::default(), user.untracked_claims(), now);
+        for bucket in &user.claims {
+            self.remove_claim(bucket.unlock_at, bucket.amount.0, now);
+        }
+    }
+
+    /// How many claimable tokens are still locked at the given time.
+    pub fn locked(
+        &self,
+        now: Timestamp,
+    ) -> u128 {
+        self.locked_claims
+            .iter_from(now)
+            .map(|(_unlock_at, locked)| locked)
+            .sum()
+    }
+
+    /// Removes the entries whose unlock date has passed.
+    fn prune(
+        &mut self,
+        now: Timestamp,
+    ) {
+        while let Some(unlock_at) = self.locked_claims.min() {
+            if unlock_at > now {
+                break;
+            }
+            self.locked_claims.remove(&unlock_at);
+        }
+    }
+}
+
+/// How the total supply is split.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SupplyBreakdown {
+    /// Every SEAT token, including the ones that must still be claimed.
+    pub total_supply: U128,
+    /// The tokens that are neither waiting to be claimed nor held by the
+    /// treasury.
+    pub circulating_supply: U128,
+    /// The tokens waiting to be claimed, including the locked ones.
+    pub claim_balance: U128,
+    /// The tokens waiting to be claimed that can't yet be claimed.
+    pub vesting_locked: U128,
+    /// The tokens held by the treasury.
+    pub treasury: U128,
+}
+
+/// The history of balances, for looking them up at a past time or mint
+/// generation.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Checkpoints {
+    /// The balance history of each account, from the oldest to the newest,
+    /// with the last balance of each mint generation.
+    pub accounts: LookupMap<AccountId, Vector<Checkpoint>>,
+    /// The total supply history, from the oldest to the newest, with the last
+    /// total supply of each mint generation.
+    pub total_supply: Vector<Checkpoint>,
+    /// How many yoctoNEAR each account has left from it's prepaid history,
+    /// which pays for the growth of it's balance and vote history.
+    pub allowances: LookupMap<AccountId, near_sdk::Balance>,
+}
+
+/// Delegation of voting power, which follows the balances of the delegators.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Delegation {
+    /// To whom each account delegates it's votes.
+    pub delegates: LookupMap<AccountId, AccountId>,
+    /// The history of the votes delegated to each account, from the oldest
+    /// to the newest, with the last votes of each mint generation.
+    pub votes: LookupMap<AccountId, Vector<Checkpoint>>,
+}
+
+/// A value that was set at some point, and that was kept until the next
+/// checkpoint.
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Checkpoint {
+    /// When the value was set.
+    pub timestamp: Timestamp,
+    /// How many mints had finished when the value was set, counting the one
+    /// in progress.
+    pub generation: u32,
+    pub value: U128,
+}
+
+/// A point in the past.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "t", content = "c")]
+pub enum PastPoint {
+    /// At the given time.
+    Timestamp(Timestamp),
+    /// After the given number of mints had finished, and before the next one
+    /// started.
+    Generation(u32),
+}
+
+impl PastPoint {
+    /// Whether the checkpoint was set at, or before, this point.
+    pub fn includes(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> bool {
+        match *self {
+            PastPoint::Timestamp(timestamp) => checkpoint.timestamp <= timestamp,
+            PastPoint::Generation(generation) => checkpoint.generation <= generation,
+        }
+    }
+}
+
+/// Proposals that token holders vote on, and which call some whitelisted
+/// actions once approved.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Governance {
+    /// If `None`, no proposals can be created.
+    pub config: Option<GovernanceConfig>,
+    /// Every proposal, by their id.
+    pub proposals: Vector<Proposal>,
+    /// The vote of each account on each proposal.
+    pub votes: LookupMap<(u64, AccountId), Vote>,
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GovernanceConfig {
+    /// How many votes an account must have to create a proposal.
+    pub proposal_threshold: U128,
+    /// How many votes must be cast in favor or in abstention for a proposal
+    /// to be approved.
+    pub quorum: U128,
+    /// For how long a proposal can be voted on.
+    pub voting_period_seconds: u32,
+}
+
+/// Actions that approved proposals can call, without being an owner.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "t", content = "c")]
+pub enum ProposalAction {
+    /// As in `change_vesting_set`.
+    ChangeVestingSet {
+        name: SetName,
+        new_start_date: Timestamp,
+        new_expiration_date: Timestamp,
+        new_reward: RewardAmount,
+        new_users_must_claim: bool,
+    },
+    /// As in `change_start_timestamp`.
+    ChangeStartTimestamp { new_start_timestamp_seconds: u32 },
+    /// As in `add_owner`.
+    AddOwner { owner_id: AccountId },
+    /// As in `remove_owner`.
+    RemoveOwner { owner_id: AccountId },
+    /// As in `set_metadata`.
+    SetMetadata {
+        metadata: near_contract_standards::fungible_token::metadata::FungibleTokenMetadata,
+    },
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer_id: AccountId,
+    pub description: String,
+    pub actions: Vec<ProposalAction>,
+    pub created_at: Timestamp,
+    /// The mint generation whose end the voting power is taken from, which
+    /// is the last one that had ended when the proposal was created.
+    pub snapshot_generation: u32,
+    /// Until when the proposal can be voted on.
+    pub voting_ends_at: Timestamp,
+    /// The quorum required when the proposal was created.
+    pub quorum: U128,
+    pub votes_for: U128,
+    pub votes_against: U128,
+    pub votes_abstain: U128,
+    pub executed: bool,
+}
+
+impl Proposal {
+    pub fn status(
+        &self,
+        now: Timestamp,
+    ) -> ProposalStatus {
+        if self.executed {
+            ProposalStatus::Executed
+        } else if now < self.voting_ends_at {
+            ProposalStatus::Active
+        } else if self.votes_for.0 + self.votes_abstain.0 >= self.quorum.0
+            && self.votes_for.0 > self.votes_against.0
+        {
+            ProposalStatus::Succeeded
+        } else {
+            ProposalStatus::Defeated
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    /// Can still be voted on.
+    Active,
+    /// Didn't reach the quorum, or didn't have more votes in favor than
+    /// against.
+    Defeated,
+    /// Approved, and can be executed.
+    Succeeded,
+    /// Approved and already executed.
+    Executed,
+}
+
+/// A proposal, with it's id and current status.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalInfo {
+    pub id: U64,
+    pub status: ProposalStatus,
+    pub proposal: Proposal,
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Vote {
+    pub kind: VoteKind,
+    /// The voting power of the account when the proposal was created.
+    pub weight: U128,
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VoteKind {
+    For,
+    Against,
+    Abstain,
+}
+
+/// Staking of SEAT tokens, where the stakers share the per-mint reward of a
+/// vesting set.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Staking {
+    pub config: StakingConfig,
+    /// How many raw SEAT tokens are staked, not counting the ones being
+    /// unstaked.
+    pub total_staked: u128,
+    /// The rewards that each staked raw token has accumulated since the
+    /// start, scaled by [`Staking::PRECISION`].
+    pub reward_per_share: u128,
+    pub stakes: LookupMap<AccountId, Stake>,
+}
+
+impl Staking {
+    /// The scale of [`Staking::reward_per_share`].
+    pub const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+    /// Moves the rewards accumulated by a stake since it was last settled
+    /// into it's pending rewards.
+    pub fn settle(
+        &self,
+        stake: &mut Stake,
+    ) {
+        let accumulated = stake
+            .staked
+            .0
+            .checked_mul(self.reward_per_share - stake.reward_per_share.0)
+            .unwrap_or_else(|| near_sdk::env::panic_str("Staking reward overflow"))
+            / Self::PRECISION;
+        stake.rewards.0 += accumulated;
+        stake.reward_per_share = self.reward_per_share.into();
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingConfig {
+    /// The vesting set whose per-mint reward is shared by the stakers,
+    /// instead of by it's members.
+    ///
+    /// If `None`, the stakers are not rewarded.
+    pub reward_set: Option<SetName>,
+    /// For how long unstaked tokens are held before they can be withdrawn.
+    pub cooldown_seconds: u32,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stake {
+    pub staked: U128,
+    /// The [`Staking::reward_per_share`] when the stake was last settled.
+    pub reward_per_share: U128,
+    /// Rewards that were settled but not yet claimed.
+    pub rewards: U128,
+    /// Tokens that were unstaked, but that are still in their cooldown.
+    pub unstaking: U128,
+    /// When the tokens being unstaked can be withdrawn.
+    pub unstaking_until: Timestamp,
+}
+
+/// Raw SEAT tokens streamed from a sender to a receiver, at a constant rate.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stream {
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    /// How many raw SEAT tokens are streamed each second.
+    pub rate_per_second: U128,
+    pub start: Timestamp,
+    pub stop: Timestamp,
+    /// How many raw SEAT tokens were taken from the sender, to be streamed.
+    pub deposit: U128,
+    /// How many raw SEAT tokens were already paid out to the receiver.
+    pub withdrawn: U128,
+    /// When the stream got canceled, if it was.
+    pub canceled_at: Option<Timestamp>,
+}
+
+impl Stream {
+    /// How many raw SEAT tokens were streamed up to `now`, which stops
+    /// growing once the stream is stopped or canceled.
+    pub fn streamed(
+        &self,
+        now: Timestamp,
+    ) -> u128 {
+        let end = self.canceled_at.unwrap_or(self.stop);
+        let end = if now < end { now } else { end };
+        if end <= self.start {
+            return 0;
+        }
+        self.rate_per_second.0 * (end - self.start).0 as u128 / Timestamp::SECS_TO_NANO as u128
+    }
+
+    /// How many raw SEAT tokens are still held by the stream.
+    pub fn held(&self) -> u128 {
+        if self.canceled_at.is_some() {
+            0
+        } else {
+            self.deposit.0 - self.withdrawn.0
+        }
+    }
+
+    /// How many raw SEAT tokens the receiver can withdraw at `now`.
+    pub fn withdrawable(
+        &self,
+        now: Timestamp,
+    ) -> u128 {
+        self.streamed(now) - self.withdrawn.0
+    }
+
+    pub fn status(
+        &self,
+        now: Timestamp,
+    ) -> StreamStatus {
+        if self.canceled_at.is_some() {
+            StreamStatus::Canceled
+        } else if now < self.start {
+            StreamStatus::Pending
+        } else if now < self.stop {
+            StreamStatus::Streaming
+        } else {
+            StreamStatus::Finished
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StreamStatus {
+    /// Not yet started.
+    Pending,
+    /// Started and not yet stopped.
+    Streaming,
+    /// Stopped, with every token already streamed.
+    Finished,
+    /// Canceled, with the tokens split between the sender and the receiver.
+    Canceled,
+}
+
+/// A stream, with it's id, current status and how much can be withdrawn.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamInfo {
+    pub id: U64,
+    pub status: StreamStatus,
+    pub withdrawable: U128,
+    pub stream: Stream,
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::let_and_return)]
 
 use crate::{
+    events,
     types::{self, RewardAmount, RewardPercentage},
     Seats,
 };
@@ -21,7 +22,10 @@ impl Seats {
             .sets
             .values()
             .filter_map(|set| {
-                if now >= set.info.start_date && now < set.info.expiration_date {
+                if now >= set.info.start_date
+                    && now < set.info.expiration_date
+                    && !set.info.removing
+                {
                     Some(set.info.reward.0)
                 } else {
                     None
@@ -31,6 +35,10 @@ impl Seats {
         RewardAmount(rewards)
     }
 
+    /// Registers a new user set.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the new
+    /// set, and any extra deposit is refunded.
     #[payable]
     pub fn add_vesting_set(
         &mut self,
@@ -42,35 +50,29 @@ impl Seats {
     ) {
         use types::Timestamp;
 
-        require!(
-            env::attached_deposit() == crate::storage_costs::USER_SET,
-            &format!(
-                "insufficient attached payment, required {} yoctoNEAR",
-                crate::storage_costs::USER_SET
-            )
-        );
-
         self.assert_owner();
         self.assert_non_migration();
         self.assert_non_minting();
 
-        let start_date = self.start_timestamp + Timestamp::from_seconds(start_delay_seconds);
-        let expiration_date = start_date + Timestamp::from_seconds(expiration_delay_seconds);
+        common::refund_deposit(|| {
+            let start_date = self.start_timestamp + Timestamp::from_seconds(start_delay_seconds);
+            let expiration_date = start_date + Timestamp::from_seconds(expiration_delay_seconds);
 
-        let user_set = self.internal_prepare_vesting_set(
-            &name,
-            start_date,
-            expiration_date,
-            reward,
-            users_must_claim,
-        );
+            let user_set = self.internal_prepare_vesting_set(
+                &name,
+                start_date,
+                expiration_date,
+                reward,
+                users_must_claim,
+            );
 
-        let previous = self.mint.vesting.sets.insert(&name, &user_set);
+            let previous = self.mint.vesting.sets.insert(&name, &user_set);
 
-        require!(
-            previous.is_none(),
-            &format!("set {} is already registered", &name.0)
-        );
+            require!(
+                previous.is_none(),
+                &format!("set {} is already registered", &name.0)
+            );
+        })
     }
 
     /// Changes a registered user set.
@@ -84,6 +86,40 @@ impl Seats {
         new_reward: RewardAmount,
         new_users_must_claim: bool,
     ) -> types::VestingUserSetInfo {
+        self.assert_owner();
+        self.internal_change_vesting_set(
+            name,
+            new_start_date,
+            new_expiration_date,
+            new_reward,
+            new_users_must_claim,
+        )
+    }
+
+    /// Unregisters a user set.
+    ///
+    /// If forced, the set is marked as being removed and it's members are
+    /// unregistered from it in pages, the first page right away. While it's
+    /// being removed, the set can't be changed and doesn't mint rewards, and
+    /// `step_remove_vesting_set` must be called until no members remain.
+    ///
+    /// The deposit for the storage freed by the set and it's members is
+    /// refunded to the caller.
+    ///
+    /// Returns the removed user set information, and how much was refunded.
+    ///
+    /// ### Parameters
+    ///
+    /// - `name`: The set name.
+    /// - `force`: Whether a set that still has members should be removed.
+    /// - `limit`: How many members to remove right away. If `null`, a limit
+    /// of 100 members is assumed.
+    pub fn remove_vesting_set(
+        &mut self,
+        name: types::SetName,
+        force: Option<bool>,
+        limit: Option<u8>,
+    ) -> types::RemovedVestingSet {
         self.assert_owner();
         self.assert_non_migration();
         self.assert_non_minting();
@@ -94,31 +130,36 @@ impl Seats {
             .sets
             .get(&name)
             .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
 
-        let previous_set = user_set.info.clone();
-        user_set.info.reward = new_reward;
-        user_set.info.start_date = new_start_date;
-        user_set.info.expiration_date = new_expiration_date;
-        user_set.info.users_must_claim = new_users_must_claim;
-
-        self.mint.vesting.sets.insert(&name, &user_set);
+        match (force, user_set.accounts.is_empty()) {
+            (_force @ Some(true), _empty @ false) => {
+                user_set.info.removing = true;
+                self.mint.vesting.sets.insert(&name, &user_set);
+            }
+            (_force, _empty @ false) => env::panic_str(&format!("set {} is not empty", name.0)),
+            (_force, _empty @ true) => {}
+        };
 
-        previous_set
+        self.internal_step_remove_vesting_set(&name, limit.unwrap_or(100))
     }
 
-    /// Unregisters a user set.
+    /// Seals a user set, permanently forbidding any changes to it's reward,
+    /// dates and members. The set can't be removed either.
     ///
-    /// Receives the registration deposit back to the caller.
+    /// ### Parameters
     ///
-    /// Returns the removed user set information.
-    pub fn remove_vesting_set(
+    /// - `name`: The set name.
+    /// - `require_full`: Whether the members percentages must add up to
+    /// exactly 100%. Defaults to `false`.
+    pub fn seal_vesting_set(
         &mut self,
         name: types::SetName,
-        force: Option<bool>,
+        require_full: Option<bool>,
     ) -> types::VestingUserSetInfo {
         self.assert_owner();
         self.assert_non_migration();
-        self.assert_non_minting();
 
         let mut user_set = self
             .mint
@@ -126,23 +167,58 @@ impl Seats {
             .sets
             .get(&name)
             .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        if require_full.unwrap_or_default() {
+            require!(
+                user_set.info.total_user_percentages == RewardPercentage::MAX,
+                &format!(
+                    "set {} percentages add up to {}, not to 100%",
+                    name.0, user_set.info.total_user_percentages.0
+                )
+            );
+        }
+
+        user_set.info.sealed = true;
+        self.mint.vesting.sets.insert(&name, &user_set);
 
-        match (force, user_set.accounts.is_empty()) {
-            (_force @ Some(true), _empty @ false) => {
-                user_set.accounts.clear();
-                self.mint.vesting.sets.remove(&name);
-            }
-            (_force, _empty @ false) => env::panic_str(&format!("set {} is not empty", name.0)),
-            (_force, _empty @ true) => {
-                self.mint.vesting.sets.remove(&name);
-            }
-        };
+        user_set.info
+    }
 
-        // returns the deposit used for creating a new user set
-        near_sdk::Promise::new(env::predecessor_account_id())
-            .transfer(crate::storage_costs::USER_SET);
+    /// Continues the removal of a user set, unregistering a page of it's
+    /// members. This should be repeatedly called until no members remain, at
+    /// which point the set itself is removed.
+    ///
+    /// The deposit for the storage freed by the set and it's members is
+    /// refunded to the caller.
+    ///
+    /// ### Parameters
+    ///
+    /// - `name`: The set name.
+    /// - `limit`: How many members to remove. If `null`, a limit of 100
+    /// members is assumed.
+    pub fn step_remove_vesting_set(
+        &mut self,
+        name: types::SetName,
+        limit: Option<u8>,
+    ) -> types::RemovedVestingSet {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
 
-        user_set.info
+        let user_set = self
+            .mint
+            .vesting
+            .sets
+            .get(&name)
+            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
+        require!(
+            user_set.info.removing,
+            &format!("set {} is not being removed", name.0)
+        );
+
+        self.internal_step_remove_vesting_set(&name, limit.unwrap_or(100))
     }
 
     /// Get a vesting set information.
@@ -158,33 +234,37 @@ impl Seats {
     }
 
     /// Get user set names.
+    ///
+    /// ### Parameters
+    ///
+    /// - `from_index`: How many sets to skip.
+    /// - `limit`: How many sets to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
     pub fn get_vesting_sets(
         &self,
         from_index: Option<near_sdk::json_types::U64>,
         limit: Option<u16>,
     ) -> Vec<types::SetName> {
-        let from_index = from_index.unwrap_or_else(|| 0.into()).0 as usize;
-        let limit = limit.unwrap_or(u16::MAX) as usize;
-
-        self.mint
-            .vesting
-            .sets
-            .keys()
-            .skip(from_index)
-            .take(limit)
+        let names = self.mint.vesting.sets.keys_as_vector();
+        crate::page_range(from_index, limit, names.len())
+            .filter_map(|index| names.get(index))
             .collect()
     }
 
     /// Get the members from a user set.
+    ///
+    /// ### Parameters
+    ///
+    /// - `name`: The set being queried.
+    /// - `from_index`: How many members to skip.
+    /// - `limit`: How many members to show. Defaults to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::DEFAULT_PAGE_LIMIT).
     pub fn get_vesting_set_users(
         &self,
         name: types::SetName,
         from_index: Option<near_sdk::json_types::U64>,
         limit: Option<u16>,
     ) -> Vec<(AccountId, RewardPercentage)> {
-        let from_index = from_index.unwrap_or_else(|| 0.into()).0 as usize;
-        let limit = limit.unwrap_or(u16::MAX) as usize;
-
         let user_set = self
             .mint
             .vesting
@@ -192,15 +272,17 @@ impl Seats {
             .get(&name)
             .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
 
-        let accs: Vec<(_, _)> = user_set
-            .accounts
-            .iter()
-            .skip(from_index)
-            .take(limit)
-            .collect();
-        accs
+        let accounts = user_set.accounts.keys_as_vector();
+        let shares = user_set.accounts.values_as_vector();
+        crate::page_range(from_index, limit, accounts.len())
+            .filter_map(|index| Some((accounts.get(index)?, shares.get(index)?)))
+            .collect()
     }
 
+    /// Registers an account as a member of a user set.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the new
+    /// member, and any extra deposit is refunded.
     #[payable]
     pub fn add_vesting_user(
         &mut self,
@@ -208,30 +290,31 @@ impl Seats {
         account_id: AccountId,
         percentage: types::RewardPercentage,
     ) {
-        require!(
-            env::attached_deposit() == crate::storage_costs::user::VESTING,
-            &format!(
-                "insufficient attached payment, required {} yoctoNEAR",
-                crate::storage_costs::user::VESTING
-            )
-        );
-
         self.assert_owner();
         self.assert_non_migration();
         self.assert_non_minting();
 
-        let mut user_set = self
-            .mint
-            .vesting
-            .sets
-            .get(&set)
-            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+        common::refund_deposit(|| {
+            let mut user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+            user_set.assert_not_removing(&set);
+            user_set.assert_not_sealed(&set);
 
-        self.internal_add_vesting_user(&set, &mut user_set, account_id, percentage);
+            self.internal_add_vesting_user(&set, &mut user_set, account_id.clone(), percentage);
 
-        self.mint.vesting.sets.insert(&set, &user_set);
+            self.mint.vesting.sets.insert(&set, &user_set);
+        })
     }
 
+    /// Unregisters an account from a user set.
+    ///
+    /// The deposit for the freed storage is refunded to the caller.
+    ///
+    /// Returns the member's previous percentage.
     pub fn remove_vesting_user(
         &mut self,
         set: types::SetName,
@@ -241,29 +324,22 @@ impl Seats {
         self.assert_non_migration();
         self.assert_non_minting();
 
-        let mut user_set = self
-            .mint
-            .vesting
-            .sets
-            .get(&set)
-            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+        common::refund_deposit(|| {
+            let mut user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+            user_set.assert_not_removing(&set);
+            user_set.assert_not_sealed(&set);
 
-        let reward = user_set.accounts.remove(&account_id).unwrap_or_else(|| {
-            env::panic_str(&format!(
-                "account {} not registered on set {}",
-                account_id, set.0
-            ))
-        });
-
-        user_set.info.total_user_percentages.0 -= reward.0;
-
-        self.mint.vesting.sets.insert(&set, &user_set);
+            let reward = self.internal_remove_vesting_user(&set, &mut user_set, &account_id);
 
-        // returns the deposit used for creating a new user set
-        near_sdk::Promise::new(env::predecessor_account_id())
-            .transfer(crate::storage_costs::user::VESTING);
+            self.mint.vesting.sets.insert(&set, &user_set);
 
-        reward
+            reward
+        })
     }
 
     pub fn change_vesting_user(
@@ -285,25 +361,218 @@ impl Seats {
             .sets
             .get(&set)
             .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+        user_set.assert_not_removing(&set);
+        user_set.assert_not_sealed(&set);
 
-        let previous = user_set
-            .accounts
-            .insert(&account_id, &new_percentage)
-            .unwrap_or_else(|| {
-                env::panic_str(&format!(
-                    "account {} not registered on set {}",
-                    account_id, set.0
-                ))
-            });
+        let previous =
+            Self::internal_change_vesting_user(&set, &mut user_set, &account_id, new_percentage);
+        user_set.info.total_user_percentages.check();
 
-        user_set.info.total_user_percentages.0 -= previous.0;
-        user_set.info.total_user_percentages.0 += new_percentage.0;
+        self.mint.vesting.sets.insert(&set, &user_set);
+        previous
+    }
+
+    /// Moves a member from a user set into another, with a new percentage.
+    ///
+    /// The storage freed from the previous set is used for the new one, and
+    /// the caller must attach a deposit for any extra storage. Any extra
+    /// deposit is refunded.
+    ///
+    /// Returns the member's previous percentage.
+    #[payable]
+    pub fn move_vesting_user(
+        &mut self,
+        from_set: types::SetName,
+        to_set: types::SetName,
+        account_id: AccountId,
+        new_percentage: RewardPercentage,
+    ) -> RewardPercentage {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        require!(
+            from_set != to_set,
+            "the sets must be different, use change_vesting_user instead"
+        );
+
+        let previous = common::refund_deposit(|| {
+            let mut from_user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&from_set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", from_set.0)));
+            from_user_set.assert_not_removing(&from_set);
+            from_user_set.assert_not_sealed(&from_set);
+            let mut to_user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&to_set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", to_set.0)));
+            to_user_set.assert_not_removing(&to_set);
+            to_user_set.assert_not_sealed(&to_set);
+
+            let previous =
+                self.internal_remove_vesting_user(&from_set, &mut from_user_set, &account_id);
+            self.internal_add_vesting_user(
+                &to_set,
+                &mut to_user_set,
+                account_id.clone(),
+                new_percentage,
+            );
+
+            self.mint.vesting.sets.insert(&from_set, &from_user_set);
+            self.mint.vesting.sets.insert(&to_set, &to_user_set);
+
+            previous
+        });
+
+        events::VestingUserMove {
+            account_id: &account_id,
+            from_set: &from_set,
+            to_set: &to_set,
+            previous_percentage: previous,
+            new_percentage,
+        }
+        .emit();
+
+        previous
+    }
+
+    /// Registers many accounts as members of a user set.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the new
+    /// members, and of the new accounts if they get registered, and any extra
+    /// deposit is refunded.
+    ///
+    /// ### Parameters
+    ///
+    /// - `set`: The set name.
+    /// - `members`: The accounts and their percentages.
+    /// - `register`: Whether accounts that are not yet registered should get
+    /// registered. Defaults to `false`, in which case they cause a panic.
+    ///
+    /// ###### Notes
+    ///
+    /// - If any member fails to be added, none of them are.
+    #[payable]
+    pub fn add_vesting_users(
+        &mut self,
+        set: types::SetName,
+        members: Vec<(AccountId, RewardPercentage)>,
+        register: Option<bool>,
+    ) {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        let register = register.unwrap_or_default();
+
+        common::refund_deposit(|| {
+            let mut user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+            user_set.assert_not_removing(&set);
+            user_set.assert_not_sealed(&set);
+
+            for (account_id, percentage) in members.clone() {
+                if register && !self.accounts.contains_key(&account_id) {
+                    self.internal_register_account(&account_id);
+                }
+                self.internal_add_vesting_user(&set, &mut user_set, account_id, percentage);
+            }
+
+            self.mint.vesting.sets.insert(&set, &user_set);
+        })
+    }
+
+    /// Changes the percentages of many members of a user set.
+    ///
+    /// The set's total percentage is only checked after all members got
+    /// changed, so percentages can be moved between the members.
+    ///
+    /// Returns the members' previous percentages, in the same order.
+    ///
+    /// ###### Notes
+    ///
+    /// - If any member fails to be changed, none of them are.
+    pub fn change_vesting_users(
+        &mut self,
+        set: types::SetName,
+        members: Vec<(AccountId, RewardPercentage)>,
+    ) -> Vec<RewardPercentage> {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        let mut user_set = self
+            .mint
+            .vesting
+            .sets
+            .get(&set)
+            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+        user_set.assert_not_removing(&set);
+        user_set.assert_not_sealed(&set);
+
+        let previous = members
+            .into_iter()
+            .map(|(account_id, new_percentage)| {
+                new_percentage.check();
+                Self::internal_change_vesting_user(&set, &mut user_set, &account_id, new_percentage)
+            })
+            .collect();
         user_set.info.total_user_percentages.check();
 
         self.mint.vesting.sets.insert(&set, &user_set);
         previous
     }
 
+    /// Unregisters many accounts from a user set.
+    ///
+    /// The deposit for the freed storage is refunded to the caller.
+    ///
+    /// Returns the members' previous percentages, in the same order.
+    ///
+    /// ###### Notes
+    ///
+    /// - If any member fails to be removed, none of them are.
+    pub fn remove_vesting_users(
+        &mut self,
+        set: types::SetName,
+        account_ids: Vec<AccountId>,
+    ) -> Vec<RewardPercentage> {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        common::refund_deposit(|| {
+            let mut user_set = self
+                .mint
+                .vesting
+                .sets
+                .get(&set)
+                .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)));
+            user_set.assert_not_removing(&set);
+            user_set.assert_not_sealed(&set);
+
+            let rewards = account_ids
+                .iter()
+                .map(|account_id| {
+                    self.internal_remove_vesting_user(&set, &mut user_set, account_id)
+                })
+                .collect();
+
+            self.mint.vesting.sets.insert(&set, &user_set);
+
+            rewards
+        })
+    }
+
     /// Get a user's reward from a set.
     pub fn get_vesting_user(
         &self,
@@ -322,6 +591,143 @@ impl Seats {
 }
 
 impl Seats {
+    /// Changes a registered user set, without checking the caller.
+    ///
+    /// Returns the previous user set information.
+    pub fn internal_change_vesting_set(
+        &mut self,
+        name: types::SetName,
+        new_start_date: types::Timestamp,
+        new_expiration_date: types::Timestamp,
+        new_reward: RewardAmount,
+        new_users_must_claim: bool,
+    ) -> types::VestingUserSetInfo {
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        let mut user_set = self
+            .mint
+            .vesting
+            .sets
+            .get(&name)
+            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        let previous_set = user_set.info.clone();
+        user_set.info.reward = new_reward;
+        user_set.info.start_date = new_start_date;
+        user_set.info.expiration_date = new_expiration_date;
+        user_set.info.users_must_claim = new_users_must_claim;
+
+        self.mint.vesting.sets.insert(&name, &user_set);
+
+        previous_set
+    }
+
+    /// Unregisters an account from the user set, returning it's percentage.
+    ///
+    /// The member's reward receiver and pending request are also removed, but
+    /// the user set is not saved.
+    pub fn internal_remove_vesting_user(
+        &mut self,
+        set: &types::SetName,
+        user_set: &mut types::VestingUserSet,
+        account_id: &AccountId,
+    ) -> RewardPercentage {
+        let reward = user_set.accounts.remove(account_id).unwrap_or_else(|| {
+            env::panic_str(&format!(
+                "account {} not registered on set {}",
+                account_id, set.0
+            ))
+        });
+
+        user_set.info.total_user_percentages.0 -= reward.0;
+        self.internal_forget_vesting_member(set, account_id);
+        reward
+    }
+
+    /// Removes the reward receiver and the pending request of a member, and
+    /// the empty claim buckets that were reserved for them.
+    fn internal_forget_vesting_member(
+        &mut self,
+        set: &types::SetName,
+        account_id: &AccountId,
+    ) {
+        let key = (set.clone(), account_id.clone());
+        if let Some(receiver_id) = self.mint.vesting.receivers.remove(&key) {
+            self.internal_release_claim_bucket(set, account_id, &receiver_id);
+        }
+        self.mint.vesting.requests.remove(&key);
+        self.internal_release_claim_bucket(set, account_id, account_id);
+    }
+
+    /// Changes the percentage of a member from the user set, returning it's
+    /// previous percentage.
+    ///
+    /// The set's total percentage is not checked, and the user set is not
+    /// saved.
+    pub fn internal_change_vesting_user(
+        set: &types::SetName,
+        user_set: &mut types::VestingUserSet,
+        account_id: &AccountId,
+        new_percentage: RewardPercentage,
+    ) -> RewardPercentage {
+        let previous = user_set
+            .accounts
+            .insert(account_id, &new_percentage)
+            .unwrap_or_else(|| {
+                env::panic_str(&format!(
+                    "account {} not registered on set {}",
+                    account_id, set.0
+                ))
+            });
+
+        user_set.info.total_user_percentages.0 -= previous.0;
+        user_set.info.total_user_percentages.0 += new_percentage.0;
+        previous
+    }
+
+    /// Unregisters a page of members from a user set, and removes the set
+    /// once it has no members left.
+    fn internal_step_remove_vesting_set(
+        &mut self,
+        name: &types::SetName,
+        limit: u8,
+    ) -> types::RemovedVestingSet {
+        let ((info, removed_members, remaining_members), refunded) =
+            common::refund_deposit_amount(|| {
+                let mut user_set = self
+                    .mint
+                    .vesting
+                    .sets
+                    .get(name)
+                    .unwrap_or_else(|| env::panic_str(&format!("missing set {}", name.0)));
+
+                let page: Vec<AccountId> = user_set.accounts.keys().take(limit as usize).collect();
+                for account_id in &page {
+                    user_set.accounts.remove(account_id);
+                    self.internal_forget_vesting_member(name, account_id);
+                }
+
+                let remaining_members = user_set.accounts.len();
+                if remaining_members == 0 {
+                    self.mint.vesting.sets.remove(name);
+                } else {
+                    self.mint.vesting.sets.insert(name, &user_set);
+                }
+
+                (user_set.info, page.len() as u64, remaining_members)
+            });
+
+        types::RemovedVestingSet {
+            info,
+            removed_members,
+            remaining_members,
+            refunded: refunded.into(),
+        }
+    }
+
     pub fn internal_prepare_vesting_set(
         &mut self,
         name: &types::SetName,
@@ -343,6 +749,12 @@ impl Seats {
                 expiration_date,
                 total_user_percentages: types::RewardPercentage::default(),
                 users_must_claim,
+                removing: false,
+                sealed: false,
+                requests_need_approval: false,
+                revocable: false,
+                claim_window: None,
+                claim_unlock_date: types::Timestamp::default(),
             },
             accounts,
         };
@@ -363,6 +775,7 @@ impl Seats {
         );
 
         let previous = user_set.accounts.insert(&account_id, &percentage);
+        self.internal_reserve_claim_bucket(set, &account_id, &account_id);
 
         user_set.info.total_user_percentages.0 += percentage.0;
         user_set.info.total_user_percentages.check();
@@ -0,0 +1,338 @@
+//! Changes that vesting members can make over their own position in a set,
+//! such as naming another account to receive their rewards, or transferring
+//! their position into another account.
+//!
+//! If the set requires approval, the changes are kept as pending requests
+//! until an owner approves them.
+//!
+//! Sealed sets reject new position transfers and their approvals, as their
+//! members can no longer change, but their members can still name receivers
+//! and their pending requests can still be canceled.
+//!
+//! On revocable sets, a member's receiver can only be replaced after it
+//! claims the tokens earned by the member, which are otherwise clawed back
+//! on a revocation.
+
+use crate::{
+    types::{self, VestingRequest},
+    Seats,
+};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SeatsContract;
+
+#[near_bindgen]
+impl Seats {
+    /// Changes whether the requests from the members of a user set must be
+    /// approved by an owner.
+    ///
+    /// Returns the previous value.
+    pub fn change_vesting_set_approval(
+        &mut self,
+        name: types::SetName,
+        requests_need_approval: bool,
+    ) -> bool {
+        self.assert_owner();
+        self.assert_non_migration();
+
+        let mut user_set = self.internal_unwrap_vesting_set(&name);
+        user_set.assert_not_removing(&name);
+        user_set.assert_not_sealed(&name);
+
+        let previous = user_set.info.requests_need_approval;
+        user_set.info.requests_need_approval = requests_need_approval;
+        self.mint.vesting.sets.insert(&name, &user_set);
+
+        previous
+    }
+
+    /// Names an account to receive the caller's rewards from a user set, or
+    /// makes the caller receive them again if `null`.
+    ///
+    /// The receiver must be registered.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the
+    /// receiver or of the request, and any extra deposit is refunded.
+    ///
+    /// Returns `true` if the change got applied, or `false` if it's waiting
+    /// for an owner approval.
+    #[payable]
+    pub fn set_vesting_receiver(
+        &mut self,
+        set: types::SetName,
+        receiver_id: Option<AccountId>,
+    ) -> bool {
+        if let Some(receiver_id) = &receiver_id {
+            self.internal_assert_registered(receiver_id);
+        }
+        self.internal_request_vesting_change(set, VestingRequest::Receiver { receiver_id })
+    }
+
+    /// Transfers the caller's position on a user set, with it's percentage,
+    /// into another registered account.
+    ///
+    /// The caller's reward receiver for the set is not kept.
+    ///
+    /// The caller must attach enough deposit to cover the storage of the new
+    /// member or of the request, and any extra deposit is refunded.
+    ///
+    /// Returns `true` if the transfer got applied, or `false` if it's waiting
+    /// for an owner approval.
+    #[payable]
+    pub fn transfer_vesting_position(
+        &mut self,
+        set: types::SetName,
+        new_account_id: AccountId,
+    ) -> bool {
+        self.internal_assert_registered(&new_account_id);
+        self.internal_request_vesting_change(set, VestingRequest::Transfer { new_account_id })
+    }
+
+    /// Approves and applies the pending request of a member.
+    ///
+    /// The caller must attach enough deposit to cover any extra storage, and
+    /// any extra deposit is refunded.
+    #[payable]
+    pub fn approve_vesting_request(
+        &mut self,
+        set: types::SetName,
+        account_id: AccountId,
+    ) -> VestingRequest {
+        self.assert_owner();
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        common::refund_deposit(|| {
+            let mut user_set = self.internal_unwrap_vesting_set(&set);
+            user_set.assert_not_removing(&set);
+
+            let request = self
+                .mint
+                .vesting
+                .requests
+                .remove(&(set.clone(), account_id.clone()))
+                .unwrap_or_else(|| {
+                    env::panic_str(&format!(
+                        "account {} has no request on set {}",
+                        account_id, set.0
+                    ))
+                });
+            assert_request_allowed(&set, &user_set, &request);
+
+            self.internal_apply_vesting_request(&set, &mut user_set, &account_id, request.clone());
+            self.mint.vesting.sets.insert(&set, &user_set);
+
+            request
+        })
+    }
+
+    /// Cancels the pending request of a member.
+    ///
+    /// Can be called by the member itself or by an owner.
+    ///
+    /// The deposit for the freed storage is refunded to the caller.
+    #[payable]
+    pub fn cancel_vesting_request(
+        &mut self,
+        set: types::SetName,
+        account_id: AccountId,
+    ) -> VestingRequest {
+        self.assert_non_migration();
+        if env::predecessor_account_id() != account_id {
+            self.assert_owner();
+        }
+
+        common::refund_deposit(|| {
+            self.mint
+                .vesting
+                .requests
+                .remove(&(set.clone(), account_id.clone()))
+                .unwrap_or_else(|| {
+                    env::panic_str(&format!(
+                        "account {} has no request on set {}",
+                        account_id, set.0
+                    ))
+                })
+        })
+    }
+
+    /// Get the account that receives a member's rewards from a set, if it's
+    /// not the member itself.
+    pub fn get_vesting_receiver(
+        &self,
+        set: types::SetName,
+        account_id: AccountId,
+    ) -> Option<AccountId> {
+        self.mint.vesting.receivers.get(&(set, account_id))
+    }
+
+    /// Get the pending request of a member from a set.
+    pub fn get_vesting_request(
+        &self,
+        set: types::SetName,
+        account_id: AccountId,
+    ) -> Option<VestingRequest> {
+        self.mint.vesting.requests.get(&(set, account_id))
+    }
+}
+
+impl Seats {
+    /// The account that should be rewarded for a member of a set.
+    ///
+    /// This is the member's receiver, unless it's no longer registered.
+    pub fn internal_vesting_beneficiary(
+        &self,
+        set: &types::SetName,
+        account_id: AccountId,
+    ) -> AccountId {
+        match self
+            .mint
+            .vesting
+            .receivers
+            .get(&(set.clone(), account_id.clone()))
+        {
+            Some(receiver_id) if self.accounts.contains_key(&receiver_id) => receiver_id,
+            _ => account_id,
+        }
+    }
+
+    pub fn internal_unwrap_vesting_set(
+        &self,
+        set: &types::SetName,
+    ) -> types::VestingUserSet {
+        self.mint
+            .vesting
+            .sets
+            .get(set)
+            .unwrap_or_else(|| env::panic_str(&format!("missing set {}", set.0)))
+    }
+
+    pub fn internal_assert_registered(
+        &self,
+        account_id: &AccountId,
+    ) {
+        require!(
+            self.accounts.contains_key(account_id),
+            &format!("account {} is not registered", account_id)
+        );
+    }
+
+    /// Applies the change requested by the caller right away, or keeps it
+    /// pending if the set requires approval.
+    fn internal_request_vesting_change(
+        &mut self,
+        set: types::SetName,
+        request: VestingRequest,
+    ) -> bool {
+        self.assert_non_migration();
+        self.assert_non_minting();
+
+        let account_id = env::predecessor_account_id();
+
+        common::refund_deposit(|| {
+            let mut user_set = self.internal_unwrap_vesting_set(&set);
+            user_set.assert_not_removing(&set);
+            assert_request_allowed(&set, &user_set, &request);
+            require!(
+                user_set.accounts.get(&account_id).is_some(),
+                &format!("account {} not registered on set {}", account_id, set.0)
+            );
+
+            if user_set.info.requests_need_approval {
+                self.mint
+                    .vesting
+                    .requests
+                    .insert(&(set.clone(), account_id.clone()), &request);
+                false
+            } else {
+                self.internal_apply_vesting_request(
+                    &set,
+                    &mut user_set,
+                    &account_id,
+                    request.clone(),
+                );
+                self.mint.vesting.sets.insert(&set, &user_set);
+                true
+            }
+        })
+    }
+
+    /// Panics if the member's current receiver still has unclaimed tokens
+    /// that were earned by the member, as they could no longer be clawed back
+    /// once the receiver changes.
+    fn internal_assert_receiver_claimed(
+        &self,
+        set: &types::SetName,
+        account_id: &AccountId,
+    ) {
+        let receiver = self
+            .mint
+            .vesting
+            .receivers
+            .get(&(set.clone(), account_id.clone()))
+            .and_then(|receiver_id| {
+                self.accounts
+                    .get(&receiver_id)
+                    .map(|user| (receiver_id, user))
+            });
+        if let Some((receiver_id, receiver)) = receiver {
+            require!(
+                !receiver.has_set_claims(set, account_id),
+                &format!(
+                    "receiver {} still has unclaimed tokens from account {} on set {}",
+                    receiver_id, account_id, set.0
+                )
+            );
+        }
+    }
+
+    /// Applies a member's request, without saving the user set.
+    fn internal_apply_vesting_request(
+        &mut self,
+        set: &types::SetName,
+        user_set: &mut types::VestingUserSet,
+        account_id: &AccountId,
+        request: VestingRequest,
+    ) {
+        // both requests replace or drop the current receiver
+        if user_set.info.revocable {
+            self.internal_assert_receiver_claimed(set, account_id);
+        }
+
+        let key = (set.clone(), account_id.clone());
+        match request {
+            VestingRequest::Receiver { receiver_id } => {
+                let receiver_id = receiver_id.filter(|receiver_id| receiver_id != account_id);
+                let previous = match &receiver_id {
+                    Some(receiver_id) => {
+                        self.internal_reserve_claim_bucket(set, account_id, receiver_id);
+                        self.mint.vesting.receivers.insert(&key, receiver_id)
+                    }
+                    None => self.mint.vesting.receivers.remove(&key),
+                };
+                match previous {
+                    Some(previous) if Some(&previous) != receiver_id.as_ref() => {
+                        self.internal_release_claim_bucket(set, account_id, &previous);
+                    }
+                    _ => {}
+                }
+            }
+            VestingRequest::Transfer { new_account_id } => {
+                let percentage = self.internal_remove_vesting_user(set, user_set, account_id);
+                self.internal_add_vesting_user(set, user_set, new_account_id, percentage);
+            }
+        }
+    }
+}
+
+/// Panics if the request would change the members of a sealed set.
+fn assert_request_allowed(
+    set: &types::SetName,
+    user_set: &types::VestingUserSet,
+    request: &VestingRequest,
+) {
+    if let VestingRequest::Transfer { .. } = request {
+        user_set.assert_not_sealed(set);
+    }
+}